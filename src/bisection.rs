@@ -3,14 +3,14 @@
 //! # Problem
 //! You have:
 //! - A set `U` containing several objects, where each object in the set can
-//! exhibit one of two kinds of behavior: "dominant", and "recessive".
+//!   exhibit one of two kinds of behavior: "dominant", and "recessive".
 //! - A test that can be ran on some subset of `U` (denoted `T`). This test must
-//! exhibit some behavior `R` if the test set `T` contains only objects with
-//! recessive behavior
+//!   exhibit some behavior `R` if the test set `T` contains only objects with
+//!   recessive behavior
 //!
 //! # Examples
 //! - A game with many mods that is doing something unwanted, and
-//! it's unknown which of the mods is causing the behavior (lag, crashes, etc).
+//!   it's unknown which of the mods is causing the behavior (lag, crashes, etc).
 //!
 //! # Solution
 //! For some subset of objects, starting with the entire set, run the test on
@@ -22,7 +22,19 @@
 //! Basically, it's depth first search, with early branch pruning, and multiple
 //! search targets which are specified by behavior they cause in a group.
 
-use std::{collections::VecDeque, ops::RangeInclusive};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::RangeInclusive,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    confidence::{Policy, Verdict},
+    error::Error,
+    strategy::{Ddmin, Strategy},
+    tester::Tester,
+};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum State {
@@ -37,11 +49,50 @@ pub trait Stateful {
     /// Note that this only takes a `&self` reference, meaning that you need to
     /// keep track of State in a way that doesn't require mutability.
     /// You may want to use a [Cell].
-    fn set_state(&self, state: &State);
+    ///
+    /// If this fails, [Stateful::state] should still be the state the object
+    /// is really in.
+    fn set_state(&self, state: &State) -> Result<(), Error>;
     /// Gets the current cached state of an object.
     fn state(&self) -> State;
-    // /// Verifies the uncached state of the object, if possible.
-    // fn verify_state(&self) -> Option<State>;
+    /// Verifies the uncached state of the object, if possible.
+    ///
+    /// This is how [Bisection] notices the real thing changing behind its
+    /// back, see [Bisection::set_on_drift]. To be able to repair that,
+    /// [Stateful::set_state] should change the real thing even if
+    /// [Stateful::state] says it's already there.
+    fn verify_state(&self) -> Option<State> {
+        None
+    }
+}
+
+/// What to do when an object's real state doesn't match its cached one, see
+/// [Bisection::set_on_drift].
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum OnDrift {
+    /// Keep going, and note it in [Report::drift].
+    #[default]
+    Warn,
+    /// Set the object back to the state it should be in, and note it in
+    /// [Report::drift].
+    Repair,
+    /// Stop with [Error::Drift].
+    Abort,
+}
+
+/// An object that wasn't in the state it was supposed to be in.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Drift {
+    pub index: usize,
+    /// The state it should have been in.
+    pub expected: State,
+    /// The state it was really in.
+    pub found: State,
+    /// How many results had been recorded when it was noticed. Any after it
+    /// might be wrong, unless it was repaired.
+    pub after: usize,
+    /// Whether it was set back to `expected`.
+    pub repaired: bool,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
@@ -55,11 +106,16 @@ pub trait Stateful {
 /// The dominant behavior is usually some program crashing or failing, caused by
 /// a dominant object that is broken, and recessive behavior is usually the same
 /// program functioning normally, where all objects are recessive (functioning).
+///
+/// A test can also come back [Behavior::Indeterminate], like `git bisect skip`,
+/// if it couldn't be judged at all (the program failed to start for some
+/// unrelated reason, for example).
 pub enum Behavior {
     #[default]
     Unknown,
     Dominant,
     Recessive,
+    Indeterminate,
 }
 
 // pub struct Entry<T: Stateful> {
@@ -67,14 +123,19 @@ pub enum Behavior {
 //     behavior: Behavior,
 // }
 
-/// A pair of indices representing the behavior of some items in a bisection.
+/// A set of indices representing the behavior of some items in a bisection.
+///
+/// The indices point into the [Bisection] object. Usually a group is a
+/// contiguous range `from..=to`, made with [Group::new], where it is an error
+/// to construct a Group such that `from > to`. Groups can also hold any set
+/// of indices at all, made with [Group::from_indices], for when objects are
+/// shuffled, clustered, or kept together because of dependencies. Either way,
+/// [Group::from] and [Group::to] are the first and last index in the group,
+/// and two groups with the same indices are equal, however they were made.
 ///
-/// `from` and `to` point to indices in the [Bisection] object, and are inclusive.
-/// It is an error to construct a Group such that `to > from`.
-/// 
 /// ## Ordering
 /// Groups are ordered by which should be tested first.
-/// 1. Groups are ranked by behavior: Unknown > Dominant > Recessive
+/// 1. Groups are ranked by behavior: Unknown > Dominant > Indeterminate > Recessive
 /// 2. Ties are broken by size: larger > smaller
 /// 3. Ties are further broken by ordering: first > last
 /// ### Examples
@@ -103,69 +164,150 @@ pub enum Behavior {
 /// let c1 = Group::new(1, 2);
 /// let c2 = Group::new(2, 3);
 /// assert!(c1 > c2);
+/// let c3 = Group::from_indices([1, 5]);
+/// assert!(c1 > c3);
 /// ```
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Group {
-    from: usize,
-    to: usize,
+    members: Members,
     behavior: Behavior,
 }
 
+/// Which indices a [Group] holds.
+#[derive(Debug, Clone)]
+enum Members {
+    /// Every index from `from` to `to`, inclusive.
+    Range { from: usize, to: usize },
+    /// Any indices, sorted and without duplicates.
+    Set(Vec<usize>),
+}
+
 impl Group {
     pub fn new(from: usize, to: usize) -> Self {
         Group {
-            from,
-            to,
+            members: Members::Range { from, to },
             behavior: Behavior::Unknown,
         }
     }
 
+    /// Make a group out of any set of indices, which don't need to be
+    /// contiguous, or in order.
+    ///
+    /// # Panics
+    /// If `indices` is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use halfwit::bisection::Group;
+    /// let group = Group::from_indices([7, 2, 4]);
+    /// assert_eq!((group.from(), group.to(), group.size()), (2, 7, 3));
+    /// assert_eq!(group.into_iter().collect::<Vec<_>>(), vec![2, 4, 7]);
+    /// // the same indices make the same group, however they're stored
+    /// assert_eq!(Group::from_indices([3, 4, 5]), Group::new(3, 5));
+    /// ```
+    pub fn from_indices<I: IntoIterator<Item = usize>>(indices: I) -> Self {
+        let mut indices: Vec<usize> = indices.into_iter().collect();
+        assert!(!indices.is_empty(), "a group needs at least one index");
+        indices.sort_unstable();
+        indices.dedup();
+        Group {
+            members: Members::Set(indices),
+            behavior: Behavior::Unknown,
+        }
+    }
+
+    /// The first index in the group.
     pub fn from(&self) -> usize {
-        self.from
+        match &self.members {
+            Members::Range { from, .. } => *from,
+            Members::Set(indices) => indices[0],
+        }
     }
 
+    /// The last index in the group.
     pub fn to(&self) -> usize {
-        self.to
+        match &self.members {
+            Members::Range { to, .. } => *to,
+            Members::Set(indices) => indices[indices.len() - 1],
+        }
     }
 
     pub fn behavior(&self) -> Behavior {
         self.behavior
     }
 
+    /// Set the behavior of this group.
+    ///
+    /// This doesn't check anything, it's [Bisection::record] that checks new
+    /// results against old ones, see [Contradiction].
     pub fn set_behavior(&mut self, behavior: Behavior) {
         self.behavior = behavior;
     }
 
+    /// Whether `index` is in this group.
+    pub fn contains(&self, index: usize) -> bool {
+        match &self.members {
+            Members::Range { from, to } => (*from..=*to).contains(&index),
+            Members::Set(indices) => indices.binary_search(&index).is_ok(),
+        }
+    }
+
+    /// Whether every index in this group is also in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use halfwit::bisection::Group;
+    /// assert!(Group::from_indices([2, 4]).is_subset(&Group::new(1, 5)));
+    /// assert!(!Group::new(1, 5).is_subset(&Group::from_indices([2, 4])));
+    /// ```
+    pub fn is_subset(&self, other: &Group) -> bool {
+        self.into_iter().all(|i| other.contains(i))
+    }
+
+    /// Whether this group holds exactly the same indices as `other`,
+    /// regardless of behavior.
+    pub fn same_indices(&self, other: &Group) -> bool {
+        self.size() == other.size() && self.into_iter().eq(other)
+    }
+
     /// Splits this group into two groups
     ///
+    /// The first group gets the first half of the indices, rounding up.
+    ///
     /// # Examples
     /// ```
     /// # use halfwit::bisection::Group;
     /// assert_eq!(
     ///     Group::new(7, 8).split(),
     ///     (Group::new(7, 7), Group::new(8, 8))
-    /// )
+    /// );
+    /// assert_eq!(
+    ///     Group::from_indices([1, 3, 5, 7, 9]).split(),
+    ///     (Group::from_indices([1, 3, 5]), Group::from_indices([7, 9]))
+    /// );
     /// ```
     pub fn split(&self) -> (Self, Self) {
-        if self.from == self.to {
+        if self.size() == 1 {
             return (self.clone(), self.clone())
         }
         // If we're recessive, we really shouldn't be splitting anyways, but it's good
         // to maintain that knowledge.
         let new_behavior = match self.behavior {
             Behavior::Recessive => Behavior::Recessive,
-            Behavior::Dominant | Behavior::Unknown => Behavior::Unknown,
-        };
-        let g1 = Self {
-            from: self.from,
-            to: self.from + (self.to - self.from) / 2,
-            behavior: new_behavior,
+            Behavior::Dominant | Behavior::Indeterminate | Behavior::Unknown => Behavior::Unknown,
         };
-        let g2 = Self {
-            from: g1.to + 1,
-            to: self.to,
-            behavior: new_behavior,
+        let (mut g1, mut g2) = match &self.members {
+            &Members::Range { from, to } => {
+                let mid = from + (to - from) / 2;
+                (Self::new(from, mid), Self::new(mid + 1, to))
+            },
+            Members::Set(indices) => {
+                let (a, b) = indices.split_at(indices.len().div_ceil(2));
+                (Self::from_indices(a.to_vec()), Self::from_indices(b.to_vec()))
+            },
         };
+        g1.behavior = new_behavior;
+        g2.behavior = new_behavior;
         (g1, g2)
     }
 
@@ -177,16 +319,45 @@ impl Group {
     /// assert_eq!(Group::new(1, 1).size(), 1);
     /// assert_eq!(Group::new(1, 2).size(), 2);
     /// assert_eq!(Group::new(2, 3).size(), 2);
+    /// assert_eq!(Group::from_indices([2, 9]).size(), 2);
     /// ```
     pub fn size(&self) -> usize {
-        self.to - self.from + 1
+        match &self.members {
+            Members::Range { from, to } => to - from + 1,
+            Members::Set(indices) => indices.len(),
+        }
+    }
+}
+
+impl PartialEq for Group {
+    fn eq(&self, other: &Self) -> bool {
+        self.behavior == other.behavior && self.same_indices(other)
+    }
+}
+
+impl Eq for Group {}
+
+/// Iterator over the indices in a [Group], in order.
+pub enum Iter<'a> {
+    Range(RangeInclusive<usize>),
+    Set(std::iter::Copied<std::slice::Iter<'a, usize>>),
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            Iter::Range(range) => range.next(),
+            Iter::Set(indices) => indices.next(),
+        }
     }
 }
 
 // Allows the `for x in &group {}` syntax
-impl IntoIterator for &Group {
+impl<'a> IntoIterator for &'a Group {
     type Item = usize;
-    type IntoIter = RangeInclusive<Self::Item>;
+    type IntoIter = Iter<'a>;
     /// Iterate through indices in the group
     /// 
     /// # Examples
@@ -203,56 +374,173 @@ impl IntoIterator for &Group {
     /// );
     /// ```
     fn into_iter(self) -> Self::IntoIter {
-        self.from()..=self.to()
+        match &self.members {
+            &Members::Range { from, to } => Iter::Range(from..=to),
+            Members::Set(indices) => Iter::Set(indices.iter().copied()),
+        }
     }
 }
 
 impl PartialOrd for Group {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Group {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         use std::cmp::Ordering::*;
         let b1 = match self.behavior {
-            Behavior::Unknown => 3,
-            Behavior::Dominant => 2,
+            Behavior::Unknown => 4,
+            Behavior::Dominant => 3,
+            Behavior::Indeterminate => 2,
             Behavior::Recessive => 1,
         };
         let b2 = match other.behavior {
-            Behavior::Unknown => 3,
-            Behavior::Dominant => 2,
+            Behavior::Unknown => 4,
+            Behavior::Dominant => 3,
+            Behavior::Indeterminate => 2,
             Behavior::Recessive => 1,
         };
-        // compare behaviors (Unknown > Dominant > Recessive)
+        // compare behaviors (Unknown > Dominant > Indeterminate > Recessive)
         match b1.cmp(&b2) {
-            result @ (Less | Greater) => return Some(result),
+            result @ (Less | Greater) => return result,
             Equal => {},
         }
         // compare sizes (4 > 2)
         match self.size().cmp(&other.size()) {
-            result @ (Less | Greater) => return Some(result),
-            Equal => {},
-        }
-        // compare from (1 > 2)
-        match self.from().cmp(&other.from()) {
-            result @ (Less | Greater) => return Some(result.reverse()),
+            result @ (Less | Greater) => return result,
             Equal => {},
         }
-        // compare to (1 > 2)
-        match self.from().cmp(&other.from()) {
-            result @ (Less | Greater) => {
-                #[cfg(debug_assertions)]
-                panic!("Two groups differed by only `to`, despite having the same `from` and `.size()`!");
-                return Some(result.reverse())
-            },
-            Equal => {return Some(Equal)},
-        }
+        // compare indices, first to last (1 > 2)
+        self.into_iter().cmp(other).reverse()
     }
 }
 
-impl Ord for Group {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        return self.partial_cmp(&other).unwrap();
+/// How a [Record]ed behavior was found out.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Source {
+    /// The group was actually tested.
+    Measured,
+    /// The behavior follows from other results, so the test was skipped. See
+    /// [Bisection::set_inference].
+    Inferred,
+}
+
+/// A group whose behavior has been recorded in a [Bisection].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Record {
+    pub group: Group,
+    pub source: Source,
+}
+
+/// An object that's dominant, but only when some other objects are missing.
+///
+/// For example, a mod that crashes unless another mod that patches it is
+/// there too. See [Bisection::set_masking].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Mask {
+    /// The object that's dominant on its own.
+    pub culprit: usize,
+    /// A minimal set of objects that make it recessive again, in order.
+    pub fixers: Vec<usize>,
+}
+
+/// A culprit found by [Bisection::peel], and how it failed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Peeled {
+    /// The culprit, or several objects if they only failed together, in
+    /// order.
+    pub culprits: Vec<usize>,
+    /// How the test failed with only the culprit enabled, see
+    /// [Tester::signature].
+    pub signature: Option<String>,
+}
+
+/// Two recorded results that can't both be right.
+///
+/// A dominant group can't be inside a recessive one, since any dominant object
+/// should make every set it's in dominant. If it happens anyway, either the
+/// test is flaky, or the objects don't follow the model in the
+/// [module docs](self) (see [Bisection::retest]). What was actually enabled
+/// for either test is given by [Bisection::configuration].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Contradiction {
+    /// The recessive group.
+    pub recessive: Record,
+    /// The dominant group inside it.
+    pub dominant: Record,
+}
+
+/// The results of double-checking a [Bisection]'s answer, see
+/// [Bisection::verify].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Verification {
+    /// How everything but the culprits behaved together, which should be
+    /// recessive, or `None` if there was nothing else to test, or it couldn't
+    /// all be tested together (see [Bisection::testable]).
+    pub rest: Option<Behavior>,
+    /// How each culprit behaved on its own, which should be dominant.
+    pub culprits: BTreeMap<usize, Behavior>,
+    /// How each interaction behaved on its own, which should be dominant.
+    pub interactions: Vec<(Vec<usize>, Behavior)>,
+}
+
+impl Verification {
+    /// Whether every test came out the way the answer says it should.
+    ///
+    /// If not, either the test is flaky, or the objects don't follow the
+    /// model in the [module docs](self), and the answer can't be trusted.
+    pub fn is_consistent(&self) -> bool {
+        self.rest.is_none_or(|b| b == Behavior::Recessive)
+            && self.culprits.values().all(|&b| b == Behavior::Dominant)
+            && self.interactions.iter().all(|(_, b)| *b == Behavior::Dominant)
     }
 }
 
+/// The outcome of a [Bisection].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Report {
+    /// Indices of every object found to be dominant, in order.
+    pub culprits: Vec<usize>,
+    /// Objects that were dominant, but only tested alongside some dominant
+    /// object they require, and which ones. See [Bisection::implicated].
+    pub implicated: BTreeMap<usize, Vec<usize>>,
+    /// How confident the test that singled out each culprit was, see
+    /// [Verdict::confidence]. For an inferred culprit, it's how confident the
+    /// tests it was inferred from were, together.
+    pub confidence: BTreeMap<usize, f64>,
+    /// Indices of objects that couldn't be narrowed down, because testing
+    /// them came back [Behavior::Indeterminate], in order.
+    pub indeterminate: Vec<usize>,
+    /// Minimal combinations of objects that are only dominant together, each
+    /// in order.
+    pub interactions: Vec<Vec<usize>>,
+    /// Objects that were left out of the search, and the state they were
+    /// pinned in. See [Bisection::pin].
+    pub pinned: BTreeMap<usize, State>,
+    /// Seeded objects that turned out to behave differently than they were
+    /// seeded, in order. See [Bisection::reverify_seeds].
+    pub wrong_seeds: Vec<usize>,
+    /// Every group whose behavior was recorded, and whether it was measured
+    /// or inferred, in order.
+    pub history: Vec<Record>,
+    /// The results of double-checking the answer, if that was done. See
+    /// [Bisection::verify].
+    pub verification: Option<Verification>,
+    /// Results that contradict each other, in the order they were found.
+    pub contradictions: Vec<Contradiction>,
+    /// Dominant objects that are fixed by other objects, which are left out
+    /// of the culprits. See [Bisection::set_masking].
+    pub masks: Vec<Mask>,
+    /// Culprits found one at a time by [Bisection::peel], in the order they
+    /// were found.
+    pub peeled: Vec<Peeled>,
+    /// Objects that were found in the wrong state before a test, see
+    /// [Bisection::set_on_drift].
+    pub drift: Vec<Drift>,
+}
+
 /// An active bisection taking place
 ///
 /// Holds no reference to the actual behavior to be tested, that's done
@@ -260,28 +548,1237 @@ impl Ord for Group {
 pub struct Bisection<T: Stateful> {
     objects: Vec<T>,
     groups: Vec<Group>,
+    /// What each object requires, see [Bisection::require].
+    requires: Vec<Vec<usize>>,
+    /// Pairs of objects that can't be enabled together, see
+    /// [Bisection::conflict].
+    conflicts: Vec<(usize, usize)>,
+    /// The state each object is pinned in, if any, see [Bisection::pin].
+    pins: Vec<Option<State>>,
+    /// Behaviors known before the search started, see [Bisection::seed].
+    seeds: BTreeMap<usize, Behavior>,
+    reverify: bool,
+    reverified: bool,
+    policy: Policy,
+    confidence: BTreeMap<usize, f64>,
+    /// Every group that was recorded, and how it behaved, in order.
+    history: Vec<Record>,
+    /// How confident each record in `history` is.
+    certainty: Vec<f64>,
+    /// Whether to infer behaviors instead of testing, see
+    /// [Bisection::set_inference].
+    inference: bool,
+    /// Recorded results that contradict each other, see [Contradiction].
+    contradictions: Vec<Contradiction>,
+    /// Whether to look for masks, see [Bisection::set_masking].
+    masking: bool,
+    masks: Vec<Mask>,
+    /// Culprits found by [Bisection::peel].
+    peeled: Vec<Peeled>,
+    /// Objects [Bisection::peel] couldn't narrow down.
+    peel_indeterminate: Vec<usize>,
+    on_drift: OnDrift,
+    drift: Vec<Drift>,
+    /// Whether [Bisection::run] should finish with [Bisection::verify].
+    verify: bool,
+    verification: Option<Verification>,
+    /// See [Bisection::set_retries].
+    retries: usize,
+    retry_delay: Duration,
+    /// Dominant groups where both halves were recessive.
+    pending: Vec<Group>,
+    interactions: Vec<Vec<usize>>,
 }
 
 impl<T: Stateful> Bisection<T> {
     pub fn new(objects: Vec<T>) -> Self {
         // one group covering all objects to start
+        let groups = match objects.len() {
+            0 => Vec::new(),
+            len => vec![Group::new(0, len - 1)],
+        };
+        Self::with_groups(objects, groups)
+    }
+
+    /// Start a bisection from some other groups than one covering everything,
+    /// for example to shuffle the objects, or to keep related objects together.
+    ///
+    /// Each object should be in at most one group, and objects in no group at
+    /// all are never tested.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::cell::Cell;
+    /// # use halfwit::bisection::*;
+    /// # use halfwit::error::Error;
+    /// # struct Mod(Cell<State>);
+    /// # impl Stateful for Mod {
+    /// #     fn set_state(&self, state: &State) -> Result<(), Error> { self.0.set(*state); Ok(()) }
+    /// #     fn state(&self) -> State { self.0.get() }
+    /// # }
+    /// let mods = (0..6).map(|_| Mod(Cell::new(State::Enabled))).collect();
+    /// // the odd mods are all from the same author, so test them separately
+    /// let groups = vec![Group::from_indices([0, 2, 4]), Group::from_indices([1, 3, 5])];
+    /// let mut bisection = Bisection::with_groups(mods, groups);
+    /// assert_eq!(bisection.next_group(), Some(Group::from_indices([0, 2, 4])));
+    /// ```
+    pub fn with_groups(objects: Vec<T>, groups: Vec<Group>) -> Self {
         Self {
-            groups: vec![Group::new(0, objects.len() - 1)],
+            groups,
+            requires: vec![Vec::new(); objects.len()],
+            conflicts: Vec::new(),
+            pins: vec![None; objects.len()],
+            seeds: BTreeMap::new(),
+            reverify: false,
+            reverified: false,
             objects,
+            policy: Policy::default(),
+            confidence: BTreeMap::new(),
+            history: Vec::new(),
+            certainty: Vec::new(),
+            inference: false,
+            contradictions: Vec::new(),
+            masking: false,
+            masks: Vec::new(),
+            peeled: Vec::new(),
+            peel_indeterminate: Vec::new(),
+            on_drift: OnDrift::Warn,
+            drift: Vec::new(),
+            verify: true,
+            retries: 0,
+            retry_delay: Duration::ZERO,
+            verification: None,
+            pending: Vec::new(),
+            interactions: Vec::new(),
         }
     }
 
+    pub fn objects(&self) -> &[T] {
+        &self.objects
+    }
+
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    /// Every group that has been recorded so far, with its behavior, in the
+    /// order they were recorded.
+    pub fn history(&self) -> &[Record] {
+        &self.history
+    }
+
+    /// Set whether to skip tests whose outcome follows from earlier results.
+    /// Defaults to `false`.
+    ///
+    /// With this set, an untested group is:
+    /// - recessive, if any group containing it was recessive, and
+    /// - dominant, if it's one half of a dominant group whose other half was
+    ///   recessive.
+    ///
+    /// The second rule assumes that a dominant group is dominant because of
+    /// at least one dominant object, rather than only some combination of
+    /// them (see [Bisection::pending_interactions]). If that's wrong, the
+    /// search reports the wrong culprits, rather than finding the
+    /// interaction. Inferred results are marked [Source::Inferred] in the
+    /// [Bisection::history].
+    pub fn set_inference(&mut self, inference: bool) {
+        self.inference = inference;
+        self.infer();
+    }
+
+    /// Set whether to look for objects that fix other objects. Defaults to
+    /// `false`.
+    ///
+    /// Normally, a recessive group is never looked at again, since it can't
+    /// hold any dominant objects. That's not true when one object fixes
+    /// another, and the broken object is only dominant without its fix. With
+    /// this set, recessive groups are split and tested too, which takes a
+    /// test for every group down to every single object. Any dominant object
+    /// found inside a recessive group is then checked with [Ddmin] for a
+    /// minimal set of objects from that group that fix it, and reported as a
+    /// [Mask] instead of a culprit.
+    ///
+    /// Since recessive groups are tested anyway, [Bisection::set_inference]
+    /// won't infer them to be recessive.
+    pub fn set_masking(&mut self, masking: bool) {
+        self.masking = masking;
+    }
+
+    /// Set whether [Bisection::run] should double-check its answer with
+    /// [Bisection::verify] once the search is over. Defaults to `true`.
+    pub fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+
+    /// Set how [Bisection::run] deals with flaky tests. Defaults to
+    /// [Policy::Once].
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = policy;
+    }
+
+    /// Set what to do when an object turns out to be in a different state
+    /// than it should be, like a launcher re-enabling mods behind our back.
+    /// Defaults to [OnDrift::Warn].
+    ///
+    /// Every object is checked with [Stateful::verify_state] right before
+    /// each test, so objects that can't verify their state are never caught.
+    pub fn set_on_drift(&mut self, on_drift: OnDrift) {
+        self.on_drift = on_drift;
+    }
+
+    /// Set how many times a failed state change is tried again before giving
+    /// up, and how long to wait in between. Defaults to no retries.
+    ///
+    /// This is for objects that are only sometimes locked, like files that a
+    /// virus scanner is looking at.
+    pub fn set_retries(&mut self, retries: usize, delay: Duration) {
+        self.retries = retries;
+        self.retry_delay = delay;
+    }
+
     /// Change the state of all elements in a [Group]
-    pub fn set_group_state(&mut self, group: &Group, state: State) {
+    ///
+    /// # Errors
+    /// If the group has an object that doesn't exist, or an object's state
+    /// couldn't be changed, even after retrying. Objects before it will have
+    /// been changed already.
+    pub fn set_group_state(&mut self, group: &Group, state: State) -> Result<(), Error> {
         for i in group {
-            self.objects.get_mut(i).unwrap().set_state(&state);
+            self.set_object_state(i, state)?;
+        }
+        Ok(())
+    }
+
+    /// Change the state of one object, retrying as set by
+    /// [Bisection::set_retries].
+    fn set_object_state(&self, index: usize, state: State) -> Result<(), Error> {
+        let object = self.objects.get(index).ok_or(Error::NoObject(index))?;
+        let mut tries = 0;
+        loop {
+            match object.set_state(&state) {
+                Ok(()) => return Ok(()),
+                Err(_) if tries < self.retries => {
+                    tries += 1;
+                    thread::sleep(self.retry_delay);
+                }
+                Err(e) => {
+                    return Err(Error::State {
+                        index,
+                        state,
+                        source: Box::new(e),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Declare that `dependent` can't work without `dependency`.
+    ///
+    /// Whenever `dependent` is enabled for a test, `dependency` is enabled
+    /// along with it, and so is anything `dependency` requires in turn.
+    pub fn require(&mut self, dependent: usize, dependency: usize) {
+        if !self.requires[dependent].contains(&dependency) {
+            self.requires[dependent].push(dependency);
+        }
+        self.regroup();
+    }
+
+    /// Declare that `a` and `b` can't be enabled at the same time.
+    ///
+    /// No test will ever enable both, even through dependencies or pins.
+    /// Untested groups that would have to are split so that `a` and `b` end up
+    /// apart, and single objects that can't be tested at all (because they
+    /// require both) are marked [Behavior::Indeterminate]. The same goes for
+    /// objects that require something [pinned](Bisection::pin) disabled.
+    pub fn conflict(&mut self, a: usize, b: usize) {
+        if !self.conflicts.contains(&(a, b)) && !self.conflicts.contains(&(b, a)) {
+            self.conflicts.push((a, b));
+        }
+        self.regroup();
+    }
+
+    /// The first conflict that testing `indices` would break, if any.
+    pub fn conflicting(&self, indices: &[usize]) -> Option<(usize, usize)> {
+        let enabled = self.configuration(indices);
+        self.conflicts
+            .iter()
+            .find(|(a, b)| enabled.binary_search(a).is_ok() && enabled.binary_search(b).is_ok())
+            .copied()
+    }
+
+    /// Whether `indices` can be tested together, without breaking a conflict,
+    /// or enabling an object that's pinned disabled.
+    pub fn testable(&self, indices: &[usize]) -> bool {
+        self.violation(indices).is_none()
+    }
+
+    /// An object that stops `indices` from being tested together, because
+    /// it's either pinned disabled, or on the wrong side of a conflict.
+    fn violation(&self, indices: &[usize]) -> Option<usize> {
+        let enabled = self.configuration(indices);
+        let pinned_off = enabled
+            .iter()
+            .find(|&&i| self.pins[i] == Some(State::Disabled))
+            .copied();
+        let conflict = self.conflicting(indices).map(|(a, b)| match self.pins[b] {
+            // `b` is enabled either way, so it's `a` that has to go
+            Some(State::Enabled) => a,
+            _ => b,
+        });
+        pinned_off.or(conflict)
+    }
+
+    /// Keep an object in `state` for every test, and leave it out of the
+    /// search entirely.
+    ///
+    /// This is for objects that always need to be there, like the game's own
+    /// libraries, or that are already known to be broken. Pinned objects are
+    /// taken out of every group, so this should be done before the search
+    /// starts. Objects pinned enabled still bring what they require along.
+    pub fn pin(&mut self, index: usize, state: State) {
+        self.pins[index] = Some(state);
+        self.remove_from_groups(index);
+        self.regroup();
+    }
+
+    /// Every pinned object, and the state it's pinned in.
+    pub fn pinned(&self) -> BTreeMap<usize, State> {
+        (0..self.pins.len())
+            .filter_map(|i| self.pins[i].map(|state| (i, state)))
+            .collect()
+    }
+
+    /// Start the search already knowing how an object behaves, so that no
+    /// tests are spent on it.
+    ///
+    /// Seeded objects are taken out of their groups, and are disabled for
+    /// every test. An object seeded dominant is reported as a culprit, and
+    /// one seeded indeterminate is reported as such. This should be done
+    /// before the search starts. To check the seeds were right after all, see
+    /// [Bisection::set_reverify_seeds].
+    ///
+    /// # Panics
+    /// If `behavior` is [Behavior::Unknown].
+    pub fn seed(&mut self, index: usize, behavior: Behavior) {
+        assert_ne!(behavior, Behavior::Unknown, "can't seed Behavior::Unknown");
+        self.remove_from_groups(index);
+        let mut group = Group::new(index, index);
+        group.set_behavior(behavior);
+        self.groups.push(group);
+        self.seeds.insert(index, behavior);
+    }
+
+    /// Set whether [Bisection::run] should test the seeded objects once the
+    /// rest of the search is over, see [Bisection::reverify_seeds]. Defaults
+    /// to `false`.
+    pub fn set_reverify_seeds(&mut self, reverify: bool) {
+        self.reverify = reverify;
+    }
+
+    /// Put the seeded objects back into the search, to check that they
+    /// really behave the way they were seeded.
+    ///
+    /// Objects seeded recessive go back as one untested group, and the rest
+    /// as one untested group each. Any that turn out differently are listed in
+    /// [Report::wrong_seeds]. Does nothing if this was already done.
+    pub fn reverify_seeds(&mut self) {
+        if self.reverified {
+            return;
+        }
+        self.reverified = true;
+        self.groups.retain(|g| !(g.size() == 1 && self.seeds.get(&g.from()) == Some(&g.behavior())));
+        let recessive: Vec<usize> = self
+            .seeds
+            .iter()
+            .filter(|(_, b)| **b == Behavior::Recessive)
+            .map(|(i, _)| *i)
+            .collect();
+        if !recessive.is_empty() {
+            self.push_untested(Group::from_indices(recessive));
+        }
+        let others: Vec<usize> = self
+            .seeds
+            .iter()
+            .filter(|(_, b)| **b != Behavior::Recessive)
+            .map(|(i, _)| *i)
+            .collect();
+        for i in others {
+            self.push_untested(Group::new(i, i));
+        }
+    }
+
+    /// Seeded objects that turned out to behave differently than they were
+    /// seeded, once [Bisection::reverify_seeds] is done, in order.
+    pub fn wrong_seeds(&self) -> Vec<usize> {
+        if !self.reverified || !self.is_done() {
+            return Vec::new();
+        }
+        let (dominant, indeterminate) = (self.dominant(), self.indeterminate());
+        self.seeds
+            .iter()
+            .filter(|(i, seeded)| {
+                let found = match () {
+                    _ if dominant.contains(i) => Behavior::Dominant,
+                    _ if indeterminate.contains(i) => Behavior::Indeterminate,
+                    _ => Behavior::Recessive,
+                };
+                found != **seeded
+            })
+            .map(|(i, _)| *i)
+            .collect()
+    }
+
+    /// Take an object out of every group it's in.
+    fn remove_from_groups(&mut self, index: usize) {
+        self.groups = std::mem::take(&mut self.groups)
+            .into_iter()
+            .filter_map(|group| match group.contains(index) {
+                false => Some(group),
+                true if group.size() == 1 => None,
+                true => {
+                    let behavior = group.behavior();
+                    let mut group = Group::from_indices(group.into_iter().filter(|&i| i != index));
+                    group.set_behavior(behavior);
+                    Some(group)
+                },
+            })
+            .collect();
+    }
+
+    /// Everything that's actually enabled when testing `indices`: the objects
+    /// themselves, those pinned enabled, and everything they require, in
+    /// order.
+    pub fn configuration(&self, indices: &[usize]) -> Vec<usize> {
+        let pinned_on = (0..self.pins.len()).filter(|&i| self.pins[i] == Some(State::Enabled));
+        let mut indices = indices.to_vec();
+        indices.extend(pinned_on);
+        self.closure(&indices)
+    }
+
+    /// Every object in `indices`, and everything they require, in order.
+    pub fn closure(&self, indices: &[usize]) -> Vec<usize> {
+        let mut included = vec![false; self.objects.len()];
+        let mut stack = indices.to_vec();
+        while let Some(i) = stack.pop() {
+            if !included[i] {
+                included[i] = true;
+                stack.extend(&self.requires[i]);
+            }
+        }
+        (0..included.len()).filter(|&i| included[i]).collect()
+    }
+
+    /// Enable exactly the objects in a [Group] (and whatever they require),
+    /// and disable every other object, except for pinned ones.
+    ///
+    /// # Errors
+    /// If an object's state couldn't be changed, see
+    /// [Bisection::set_group_state], or an object was found in the wrong
+    /// state, and [Bisection::set_on_drift] says to stop.
+    pub fn isolate(&mut self, group: &Group) -> Result<(), Error> {
+        self.isolate_indices(&group.into_iter().collect::<Vec<_>>())
+    }
+
+    /// Enable exactly the objects whose index is in `indices` (and whatever
+    /// they require), and disable every other object, except for pinned ones.
+    ///
+    /// See [Bisection::configuration].
+    pub fn isolate_indices(&mut self, indices: &[usize]) -> Result<(), Error> {
+        let enabled = self.configuration(indices);
+        for (i, object) in self.objects.iter().enumerate() {
+            let state = match (self.pins[i], enabled.binary_search(&i)) {
+                (Some(pinned), _) => pinned,
+                (None, Ok(_)) => State::Enabled,
+                (None, Err(_)) => State::Disabled,
+            };
+            if object.state() != state {
+                self.set_object_state(i, state)?;
+            }
+        }
+        self.check_drift()
+    }
+
+    /// Make sure every object really is in the state it says it's in, see
+    /// [Bisection::set_on_drift].
+    fn check_drift(&mut self) -> Result<(), Error> {
+        for index in 0..self.objects.len() {
+            let expected = self.objects[index].state();
+            let Some(found) = self.objects[index].verify_state().filter(|&found| found != expected) else {
+                continue;
+            };
+            if self.on_drift == OnDrift::Abort {
+                return Err(Error::Drift { index, expected, found });
+            }
+            let repaired = self.on_drift == OnDrift::Repair;
+            if repaired {
+                self.set_object_state(index, expected)?;
+                if self.objects[index].verify_state().is_some_and(|now| now != expected) {
+                    return Err(Error::Drift { index, expected, found });
+                }
+            }
+            // left alone, it'd be noticed again before every test
+            let last = self.drift.iter().rev().find(|d| d.index == index);
+            if repaired || last.is_none_or(|d| (d.expected, d.found) != (expected, found)) {
+                self.drift.push(Drift {
+                    index,
+                    expected,
+                    found,
+                    after: self.history.len(),
+                    repaired,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the search is over.
+    ///
+    /// The search is over once every group is either recessive, or a single
+    /// dominant or indeterminate object, and every interaction has been
+    /// narrowed down.
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+            && self
+                .groups
+                .iter()
+                .all(|g| g.behavior() != Behavior::Unknown)
+    }
+
+    /// Dominant groups that still need narrowing down, because both of their
+    /// halves were recessive.
+    ///
+    /// This means the group's objects are only dominant in some combination,
+    /// rather than any of them being dominant alone. [Bisection::run] narrows
+    /// these down to a minimal combination with [Ddmin], but when driving the
+    /// search step by step, that's left to the caller, who should then
+    /// [Bisection::record_interaction] the result.
+    pub fn pending_interactions(&self) -> &[Group] {
+        &self.pending
+    }
+
+    /// Record the minimal combination that a pending interaction in `group`
+    /// was narrowed down to.
+    pub fn record_interaction(&mut self, group: &Group, mut minimal: Vec<usize>) {
+        self.pending.retain(|g| !g.same_indices(group));
+        minimal.sort_unstable();
+        self.interactions.push(minimal);
+    }
+
+    /// Indices of every object found to be dominant so far, in order.
+    ///
+    /// Objects that were only dominant because of something they require are
+    /// left out, see [Bisection::implicated], and so are ones that other
+    /// objects fix, see [Bisection::set_masking].
+    pub fn culprits(&self) -> Vec<usize> {
+        let implicated = self.implicated();
+        self.dominant()
+            .into_iter()
+            .filter(|i| !implicated.contains_key(i) && !self.masks.iter().any(|m| m.culprit == *i))
+            .collect()
+    }
+
+    /// Objects that were dominant, but require some other dominant object, and
+    /// which dominant objects they require.
+    ///
+    /// These can't be tested without their dependencies, so they might be fine
+    /// on their own, or broken too, there's no way to tell.
+    pub fn implicated(&self) -> BTreeMap<usize, Vec<usize>> {
+        let dominant = self.dominant();
+        dominant
+            .iter()
+            .filter_map(|&i| {
+                let through: Vec<usize> = self
+                    .closure(&[i])
+                    .into_iter()
+                    .filter(|&d| d != i && dominant.contains(&d))
+                    .collect();
+                (!through.is_empty()).then_some((i, through))
+            })
+            .collect()
+    }
+
+    /// Every single object that was dominant, in order.
+    fn dominant(&self) -> Vec<usize> {
+        let mut dominant: Vec<usize> = self
+            .groups
+            .iter()
+            .filter(|g| g.behavior() == Behavior::Dominant && g.size() == 1)
+            .map(|g| g.from())
+            .collect();
+        dominant.sort_unstable();
+        dominant
+    }
+
+    /// Indices of every object that couldn't be narrowed down so far, by the
+    /// search or by [Bisection::peel], in order.
+    pub fn indeterminate(&self) -> Vec<usize> {
+        let mut indeterminate: Vec<usize> = self
+            .groups
+            .iter()
+            .filter(|g| g.behavior() == Behavior::Indeterminate)
+            .flatten()
+            .chain(self.peel_indeterminate.iter().copied())
+            .collect();
+        indeterminate.sort_unstable();
+        indeterminate.dedup();
+        indeterminate
+    }
+
+    /// A [Report] of what the search has found so far.
+    pub fn report(&self) -> Report {
+        Report {
+            culprits: self.culprits(),
+            implicated: self.implicated(),
+            confidence: self.confidence.clone(),
+            indeterminate: self.indeterminate(),
+            interactions: self.interactions.clone(),
+            pinned: self.pinned(),
+            wrong_seeds: self.wrong_seeds(),
+            history: self.history.clone(),
+            verification: self.verification.clone(),
+            contradictions: self.contradictions.clone(),
+            masks: self.masks.clone(),
+            peeled: self.peeled.clone(),
+            drift: self.drift.clone(),
+        }
+    }
+
+    /// Indices of every currently enabled object, in order.
+    pub fn enabled(&self) -> Vec<usize> {
+        self.objects
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| o.state() == State::Enabled)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Run the search to completion, and [Bisection::report] the results.
+    ///
+    /// The highest priority group (see [Group]'s ordering) is isolated with
+    /// [Bisection::isolate], and then `tester` is called to find out how the
+    /// isolated objects behave. Recessive groups are left alone, and dominant
+    /// groups are split until only single objects remain. Indeterminate groups
+    /// are split too, so that each half gets a chance to be tested on its own,
+    /// and single indeterminate objects are given up on.
+    ///
+    /// If a dominant group's halves both turn out to be recessive, then the
+    /// group only fails because of some combination of its objects. Once the
+    /// rest of the search is over, these are narrowed down with [Ddmin], and
+    /// reported as [Report::interactions]. Finally, any seeds are reverified,
+    /// if [Bisection::set_reverify_seeds] was set, and the answer is
+    /// double-checked with [Bisection::verify], unless
+    /// [Bisection::set_verify] was turned off.
+    ///
+    /// Each group is tested as many times as the [Policy] set with
+    /// [Bisection::set_policy] asks for.
+    ///
+    /// To drive the search one step at a time instead, see
+    /// [Bisection::next_group] and [Bisection::record].
+    ///
+    /// # Errors
+    /// See [Bisection::isolate], or if the tester ran into an error (see
+    /// [Tester::take_error]). The group being tested is left untested,
+    /// so the search can pick up where it left off by calling this again.
+    ///
+    /// # Panics
+    /// If `tester` returns [Behavior::Unknown].
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::cell::Cell;
+    /// # use halfwit::bisection::*;
+    /// # use halfwit::error::Error;
+    /// struct Mod(Cell<State>);
+    /// impl Stateful for Mod {
+    ///     fn set_state(&self, state: &State) -> Result<(), Error> { self.0.set(*state); Ok(()) }
+    ///     fn state(&self) -> State { self.0.get() }
+    /// }
+    /// let mut bisection = Bisection::new((0..8).map(|_| Mod(Cell::new(State::Enabled))).collect());
+    /// // mods 2 and 5 crash the game
+    /// let report = bisection.run(|enabled: &[usize]| {
+    ///     match enabled.contains(&2) || enabled.contains(&5) {
+    ///         true => Behavior::Dominant,
+    ///         false => Behavior::Recessive,
+    ///     }
+    /// }).unwrap();
+    /// assert_eq!(report.culprits, vec![2, 5]);
+    /// ```
+    pub fn run<X: Tester>(&mut self, mut tester: X) -> Result<Report, Error> {
+        loop {
+            while let Some(group) = self.next_group() {
+                self.isolate(&group)?;
+                let verdict = self.judge(&mut tester)?;
+                self.record_verdict(&group, verdict);
+            }
+            while let Some(group) = self.pending.first().cloned() {
+                // both halves are already known to be recessive
+                let mut ddmin = Ddmin::with_pieces(group.into_iter().collect(), 4);
+                self.search_with(&mut ddmin, &mut tester)?;
+                self.record_interaction(&group, ddmin.current().to_vec());
+            }
+            if self.reverify && !self.reverified {
+                self.reverify_seeds();
+                continue;
+            }
+            if self.masking {
+                self.find_masks(&mut tester)?;
+            }
+            if self.verify && !self.objects.is_empty() {
+                self.verify_with(&mut tester)?;
+            }
+            return Ok(self.report());
+        }
+    }
+
+    /// Look for fixers for every dominant object inside a recessive group.
+    fn find_masks<X: Tester>(&mut self, tester: &mut X) -> Result<(), Error> {
+        for culprit in self.dominant() {
+            if self.masks.iter().any(|m| m.culprit == culprit) {
+                continue;
+            }
+            // the smallest recessive group it was in
+            let recessive = self
+                .history
+                .iter()
+                .filter(|r| r.source == Source::Measured && r.group.behavior() == Behavior::Recessive)
+                .map(|r| &r.group)
+                .filter(|g| g.contains(culprit))
+                .min_by_key(|g| g.size());
+            let Some(recessive) = recessive else {
+                continue;
+            };
+            let base = self.configuration(&[culprit]);
+            let candidates: Vec<usize> = recessive.into_iter().filter(|i| !base.contains(i)).collect();
+            if candidates.is_empty() {
+                // it's both recessive and dominant on its own, which is just flaky
+                continue;
+            }
+            let mut fixers = Fixers {
+                ddmin: Ddmin::new(candidates),
+                culprit,
+            };
+            self.search_with(&mut fixers, tester)?;
+            self.masks.push(Mask {
+                culprit,
+                fixers: fixers.ddmin.current().to_vec(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Double-check the answer, once the search is over.
+    ///
+    /// Everything except the culprits, interactions, and indeterminate
+    /// objects is tested together, which should be recessive. Then each
+    /// culprit and interaction is tested on its own, which should be
+    /// dominant. Pinned objects and dependencies are enabled as usual. If the
+    /// rest isn't [Bisection::testable] together, it's skipped, and any
+    /// culprit or interaction that isn't comes out [Behavior::Indeterminate].
+    /// Seeded objects are left out of all of it, unless
+    /// [Bisection::set_reverify_seeds] was set.
+    ///
+    /// The results are also kept for [Bisection::report]. Any surprises mean
+    /// the test is flaky, or the objects don't follow the model in the
+    /// [module docs](self), see [Verification::is_consistent].
+    pub fn verify<X: Tester>(&mut self, mut tester: X) -> Result<Verification, Error> {
+        self.verify_with(&mut tester)
+    }
+
+    fn verify_with<X: Tester>(&mut self, tester: &mut X) -> Result<Verification, Error> {
+        // seeds cost no tests, unless they're being reverified anyway
+        let seeded = |i: &usize| !(self.reverify || self.reverified) && self.seeds.contains_key(i);
+        let culprits: Vec<usize> = self.dominant().into_iter().filter(|i| !seeded(i)).collect();
+        let interactions = self.interactions.clone();
+        let suspicious: Vec<usize> = self
+            .dominant()
+            .iter()
+            .chain(interactions.iter().flatten())
+            .chain(self.indeterminate().iter())
+            .copied()
+            .collect();
+        let rest: Vec<usize> = (0..self.objects.len())
+            .filter(|i| self.pins[*i].is_none() && !suspicious.contains(i) && !seeded(i))
+            .collect();
+        let mut verification = Verification::default();
+        // conflicts can keep the rest from ever being tested together
+        if !rest.is_empty() && self.testable(&rest) {
+            verification.rest = Some(self.check(&rest, tester)?);
+        }
+        for culprit in culprits {
+            let behavior = self.check(&[culprit], tester)?;
+            verification.culprits.insert(culprit, behavior);
+        }
+        for interaction in interactions {
+            let behavior = self.check(&interaction, tester)?;
+            verification.interactions.push((interaction, behavior));
+        }
+        self.verification = Some(verification.clone());
+        Ok(verification)
+    }
+
+    /// Test `indices` outside of any search.
+    fn check<X: Tester>(&mut self, indices: &[usize], tester: &mut X) -> Result<Behavior, Error> {
+        if !self.testable(indices) {
+            return Ok(Behavior::Indeterminate);
+        }
+        self.isolate_indices(indices)?;
+        Ok(self.judge(tester)?.behavior)
+    }
+
+    /// Test the currently enabled objects, as many times as the [Policy]
+    /// asks for, stopping if the tester ran into an error.
+    fn judge<X: Tester>(&self, tester: &mut X) -> Result<Verdict, Error> {
+        let verdict = self.policy.judge(tester, &self.enabled());
+        match tester.take_error() {
+            Some(e) => Err(e),
+            None => Ok(verdict),
+        }
+    }
+
+    /// Find culprits one at a time, for programs that stop at the first
+    /// broken object they come across.
+    ///
+    /// A program like that hides every later culprit behind the first one,
+    /// so only the first one can be trusted. Every object that isn't pinned
+    /// is tested together, and if that's dominant, halved until one culprit
+    /// is found (or a minimal combination, using [Ddmin], if both halves are
+    /// recessive). That culprit is then [pinned](Bisection::pin) disabled,
+    /// and it all starts over with what's left, until what's left is
+    /// recessive.
+    ///
+    /// If neither half is dominant, and either one is
+    /// [indeterminate](Behavior::Indeterminate), there's no telling where the
+    /// culprit is. Peeling stops there, and the halves are reported as
+    /// [Report::indeterminate] instead, without pinning anything.
+    ///
+    /// Each culprit is reported with the [Tester::signature] of the test
+    /// that singled it out, in [Report::peeled]. This doesn't use the
+    /// bisection's own search at all, other than taking the culprits out of
+    /// it.
+    pub fn peel<X: Tester>(&mut self, mut tester: X) -> Result<Report, Error> {
+        loop {
+            let rest: Vec<usize> = (0..self.objects.len()).filter(|&i| self.pins[i].is_none()).collect();
+            if rest.is_empty() || self.check(&rest, &mut tester)? != Behavior::Dominant {
+                return Ok(self.report());
+            }
+            let mut suspects = rest;
+            let mut signature = tester.signature();
+            while suspects.len() > 1 {
+                let (a, b) = suspects.split_at(suspects.len().div_ceil(2));
+                let (a, b) = (a.to_vec(), b.to_vec());
+                let first = self.check(&a, &mut tester)?;
+                if first == Behavior::Dominant {
+                    (suspects, signature) = (a, tester.signature());
+                    continue;
+                }
+                let second = self.check(&b, &mut tester)?;
+                if second == Behavior::Dominant {
+                    (suspects, signature) = (b, tester.signature());
+                } else if first == Behavior::Indeterminate || second == Behavior::Indeterminate {
+                    self.peel_indeterminate.extend(suspects);
+                    return Ok(self.report());
+                } else {
+                    // both halves are known to be recessive
+                    let mut ddmin = Ddmin::with_pieces(suspects.clone(), 4);
+                    self.search_with(&mut ddmin, &mut tester)?;
+                    suspects = ddmin.current().to_vec();
+                    // get the signature for the combination itself
+                    self.check(&suspects, &mut tester)?;
+                    signature = tester.signature();
+                    break;
+                }
+            }
+            for &culprit in &suspects {
+                self.pin(culprit, State::Disabled);
+            }
+            self.peeled.push(Peeled {
+                culprits: suspects,
+                signature,
+            });
+        }
+    }
+
+    /// Find a 1-minimal set of objects that's still dominant, using [Ddmin].
+    ///
+    /// Rather than finding every dominant object, this finds a smallest set
+    /// that reproduces the dominant behavior, where removing any one object
+    /// makes it recessive. It's the right tool for when the behavior needs
+    /// several objects cooperating, rather than any one of them being broken.
+    ///
+    /// Only objects that aren't [pinned](Bisection::pin) are minimized. Returns
+    /// `None` if every object together isn't dominant to begin with, or if
+    /// they can't all be enabled together (see [Bisection::testable]). This
+    /// doesn't touch the bisection's own groups.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::cell::Cell;
+    /// # use halfwit::bisection::*;
+    /// # use halfwit::error::Error;
+    /// # struct Mod(Cell<State>);
+    /// # impl Stateful for Mod {
+    /// #     fn set_state(&self, state: &State) -> Result<(), Error> { self.0.set(*state); Ok(()) }
+    /// #     fn state(&self) -> State { self.0.get() }
+    /// # }
+    /// let mut bisection = Bisection::new((0..8).map(|_| Mod(Cell::new(State::Enabled))).collect());
+    /// // mods 1, 4, and 5 only crash together
+    /// let minimal = bisection.minimize(|enabled: &[usize]| {
+    ///     match [1, 4, 5].iter().all(|i| enabled.contains(i)) {
+    ///         true => Behavior::Dominant,
+    ///         false => Behavior::Recessive,
+    ///     }
+    /// }).unwrap();
+    /// assert_eq!(minimal, Some(vec![1, 4, 5]));
+    /// ```
+    pub fn minimize<X: Tester>(&mut self, mut tester: X) -> Result<Option<Vec<usize>>, Error> {
+        let all: Vec<usize> = (0..self.objects.len()).filter(|&i| self.pins[i].is_none()).collect();
+        if all.is_empty() || !self.testable(&all) {
+            return Ok(None);
+        }
+        self.isolate_indices(&all)?;
+        if self.judge(&mut tester)?.behavior != Behavior::Dominant {
+            return Ok(None);
+        }
+        let mut ddmin = Ddmin::new(all);
+        self.search_with(&mut ddmin, &mut tester)?;
+        Ok(ddmin.minimal().map(<[usize]>::to_vec))
+    }
+
+    /// Like [Bisection::run], but with a different [Strategy] deciding what to
+    /// test, instead of this bisection's own groups.
+    ///
+    /// The report comes from the strategy, and the [Policy] is still used to
+    /// run each test. Strategies don't know about conflicts or pins, so any
+    /// test that isn't [Bisection::testable] is recorded as
+    /// [Behavior::Indeterminate] without running it.
+    pub fn search<S: Strategy, X: Tester>(&mut self, strategy: &mut S, mut tester: X) -> Result<Report, Error> {
+        self.search_with(strategy, &mut tester)
+    }
+
+    fn search_with<S: Strategy, X: Tester>(&mut self, strategy: &mut S, tester: &mut X) -> Result<Report, Error> {
+        while let Some(test) = strategy.next_test() {
+            if !self.testable(&test) {
+                strategy.record(&test, Behavior::Indeterminate);
+                continue;
+            }
+            self.isolate_indices(&test)?;
+            let verdict = self.judge(tester)?;
+            strategy.record(&test, verdict.behavior);
+        }
+        Ok(strategy.report())
+    }
+
+    /// The next group that needs testing, or `None` if the search is over.
+    ///
+    /// This doesn't change any state, it's up to the caller to test the group
+    /// (usually with [Bisection::isolate]), and then [Bisection::record] the
+    /// result. Calling it again before recording a result returns the same
+    /// group.
+    ///
+    /// # Examples
+    /// ```
+    /// # use halfwit::bisection::*;
+    /// # use halfwit::error::Error;
+    /// # struct Mod;
+    /// # impl Stateful for Mod {
+    /// #     fn set_state(&self, state: &State) -> Result<(), Error> { Ok(()) }
+    /// #     fn state(&self) -> State { State::Enabled }
+    /// # }
+    /// let mut bisection = Bisection::new(vec![Mod, Mod, Mod, Mod]);
+    /// // the first test is always everything
+    /// let group = bisection.next_group().unwrap();
+    /// assert_eq!(group, Group::new(0, 3));
+    /// bisection.record(&group, Behavior::Dominant);
+    /// // which, being dominant, is then split in half
+    /// let group = bisection.next_group().unwrap();
+    /// assert_eq!(group, Group::new(0, 1));
+    /// bisection.record(&group, Behavior::Recessive);
+    /// let group = bisection.next_group().unwrap();
+    /// assert_eq!(group, Group::new(2, 3));
+    /// # bisection.record(&group, Behavior::Dominant);
+    /// # bisection.record(&Group::new(2, 2), Behavior::Recessive);
+    /// # bisection.record(&Group::new(3, 3), Behavior::Dominant);
+    /// # assert_eq!(bisection.next_group(), None);
+    /// # assert_eq!(bisection.culprits(), vec![3]);
+    /// ```
+    pub fn next_group(&self) -> Option<Group> {
+        self.next_index().map(|i| self.groups[i].clone())
+    }
+
+    /// Record the behavior of a group that was tested.
+    ///
+    /// Groups don't need to be recorded in the order [Bisection::next_group]
+    /// gives them, any untested group in [Bisection::groups] can be recorded.
+    ///
+    /// # Panics
+    /// If `group` isn't an untested group in this bisection, or `behavior` is
+    /// [Behavior::Unknown].
+    pub fn record(&mut self, group: &Group, behavior: Behavior) {
+        self.record_verdict(group, Verdict::certain(behavior));
+    }
+
+    /// Like [Bisection::record], but for a [Verdict] that might not be
+    /// certain, such as one from [Policy::judge].
+    pub fn record_verdict(&mut self, group: &Group, verdict: Verdict) {
+        let behavior = verdict.behavior;
+        assert_ne!(behavior, Behavior::Unknown, "can't record Behavior::Unknown");
+        let index = self
+            .groups
+            .iter()
+            .position(|g| g.behavior() == Behavior::Unknown && g.same_indices(group))
+            .expect("group isn't waiting to be tested");
+        self.resolve(index, behavior, Source::Measured, verdict.confidence);
+        self.infer();
+    }
+
+    /// Take an untested group out of `groups`, and put it back with a
+    /// behavior, which is right with some `confidence`.
+    fn resolve(&mut self, index: usize, behavior: Behavior, source: Source, confidence: f64) {
+        let mut group = self.groups.swap_remove(index);
+        group.set_behavior(behavior);
+        if behavior == Behavior::Dominant && group.size() == 1 {
+            self.confidence.insert(group.from(), confidence);
+        }
+        let record = Record {
+            group: group.clone(),
+            source,
+        };
+        self.check_contradictions(&record);
+        self.history.push(record);
+        self.certainty.push(confidence);
+        if behavior == Behavior::Recessive {
+            self.check_interaction(&group);
+        }
+        self.apply(group, behavior);
+    }
+
+    /// Check a new record against every earlier one.
+    fn check_contradictions(&mut self, record: &Record) {
+        let group = &record.group;
+        let found = self.history.iter().filter_map(|earlier| {
+            let other = &earlier.group;
+            match (group.behavior(), other.behavior()) {
+                (Behavior::Dominant, Behavior::Recessive) if group.is_subset(other) => Some(Contradiction {
+                    recessive: earlier.clone(),
+                    dominant: record.clone(),
+                }),
+                (Behavior::Recessive, Behavior::Dominant) if other.is_subset(group) => Some(Contradiction {
+                    recessive: record.clone(),
+                    dominant: earlier.clone(),
+                }),
+                _ => None,
+            }
+        });
+        let found: Vec<Contradiction> = found.collect();
+        self.contradictions.extend(found);
+    }
+
+    /// Every contradiction between recorded results found so far, see
+    /// [Contradiction].
+    pub fn contradictions(&self) -> &[Contradiction] {
+        &self.contradictions
+    }
+
+    /// Test both sides of a contradiction again, to see which one was wrong.
+    ///
+    /// Returns the new behavior of the recessive group and the dominant group,
+    /// in that order. This is outside of the search, and doesn't change any
+    /// recorded results. If they still contradict each other, the objects
+    /// really don't follow the model in the [module docs](self), for example
+    /// because one object fixes another (see [Bisection::set_masking]).
+    pub fn retest<X: Tester>(
+        &mut self,
+        contradiction: &Contradiction,
+        mut tester: X,
+    ) -> Result<(Behavior, Behavior), Error> {
+        let recessive: Vec<usize> = contradiction.recessive.group.into_iter().collect();
+        let dominant: Vec<usize> = contradiction.dominant.group.into_iter().collect();
+        Ok((self.check(&recessive, &mut tester)?, self.check(&dominant, &mut tester)?))
+    }
+
+    /// Resolve every untested group whose behavior follows from the history,
+    /// if [Bisection::set_inference] is on.
+    fn infer(&mut self) {
+        if !self.inference {
+            return;
+        }
+        // along with how confident each one is
+        let recorded = |behavior: Behavior| {
+            self.history
+                .iter()
+                .map(|r| &r.group)
+                .zip(self.certainty.iter().copied())
+                .filter(move |(g, _)| g.behavior() == behavior)
+        };
+        let inferred = self.groups.iter().enumerate().find_map(|(i, g)| {
+            if g.behavior() != Behavior::Unknown {
+                return None;
+            }
+            if !self.masking {
+                if let Some((_, c)) = recorded(Behavior::Recessive).find(|(r, _)| g.is_subset(r)) {
+                    return Some((i, Behavior::Recessive, c));
+                }
+            }
+            let sibling_recessive = recorded(Behavior::Dominant).find_map(|(p, c)| {
+                let (a, b) = p.split();
+                let sibling = match (p.size() > 1, a.same_indices(g), b.same_indices(g)) {
+                    (false, _, _) => return None,
+                    (true, true, _) => b,
+                    (true, _, true) => a,
+                    _ => return None,
+                };
+                // both had to be right for this to be
+                let (_, d) = recorded(Behavior::Recessive).find(|(r, _)| r.same_indices(&sibling))?;
+                Some(c * d)
+            });
+            sibling_recessive.map(|c| (i, Behavior::Dominant, c))
+        });
+        if let Some((index, behavior, confidence)) = inferred {
+            self.resolve(index, behavior, Source::Inferred, confidence);
+            // one inference can lead to another
+            self.infer();
+        }
+    }
+
+    /// Check whether a recessive group's parent was dominant even though its
+    /// sibling was recessive too.
+    fn check_interaction(&mut self, group: &Group) {
+        let recessive = |g: &Group| {
+            self.history
+                .iter()
+                .any(|h| h.group.behavior() == Behavior::Recessive && h.group.same_indices(g))
+        };
+        // an inferred parent might just have been inferred wrong
+        let measured = self.history.iter().filter(|r| r.source == Source::Measured);
+        let parent = measured.map(|r| &r.group).find(|p| {
+            let (a, b) = p.split();
+            p.behavior() == Behavior::Dominant
+                && p.size() > 1
+                && (a.same_indices(group) && recessive(&b) || b.same_indices(group) && recessive(&a))
+        });
+        if let Some(parent) = parent.cloned() {
+            self.pending.push(parent);
+        }
+    }
+
+    /// Index into `groups` of the next group that needs testing.
+    fn next_index(&self) -> Option<usize> {
+        self.groups
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.behavior() == Behavior::Unknown)
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// Put a tested group back into `groups`, splitting it if it's dominant or
+    /// indeterminate, or recessive while looking for masks.
+    fn apply(&mut self, mut group: Group, behavior: Behavior) {
+        group.set_behavior(behavior);
+        let split = match behavior {
+            Behavior::Dominant | Behavior::Indeterminate => true,
+            Behavior::Recessive => self.masking,
+            Behavior::Unknown => false,
+        };
+        if split && group.size() > 1 {
+            let (mut g1, mut g2) = group.split();
+            g1.set_behavior(Behavior::Unknown);
+            g2.set_behavior(Behavior::Unknown);
+            self.push_untested(g1);
+            self.push_untested(g2);
+        } else {
+            self.groups.push(group);
+        }
+    }
+
+    /// Add an untested group to `groups`, splitting it up first if it isn't
+    /// [Bisection::testable].
+    fn push_untested(&mut self, mut group: Group) {
+        let indices: Vec<usize> = group.into_iter().collect();
+        let Some(b) = self.violation(&indices) else {
+            self.groups.push(group);
+            return;
+        };
+        if group.size() == 1 {
+            // needs something it can't have, so it can never be tested
+            group.set_behavior(Behavior::Indeterminate);
+            self.groups.push(group);
+            return;
+        }
+        // keep everything that needs `b` away from everything else
+        let (needs_b, rest): (Vec<usize>, Vec<usize>) = indices
+            .iter()
+            .partition(|&&i| self.closure(&[i]).binary_search(&b).is_ok());
+        let (g1, g2) = match needs_b.is_empty() || rest.is_empty() {
+            true => group.split(),
+            false => (Group::from_indices(needs_b), Group::from_indices(rest)),
+        };
+        self.push_untested(g1);
+        self.push_untested(g2);
+    }
+
+    /// Check every untested group against the conflicts again.
+    fn regroup(&mut self) {
+        let (untested, tested) = std::mem::take(&mut self.groups)
+            .into_iter()
+            .partition(|g| g.behavior() == Behavior::Unknown);
+        self.groups = tested;
+        for group in untested {
+            self.push_untested(group);
         }
     }
 }
 
+/// Finds a minimal set of objects that make a dominant object recessive, by
+/// running [Ddmin] with the culprit always enabled, and the behaviors swapped.
+struct Fixers {
+    ddmin: Ddmin,
+    culprit: usize,
+}
+
+impl Strategy for Fixers {
+    fn next_test(&self) -> Option<Vec<usize>> {
+        let mut test = self.ddmin.next_test()?;
+        test.push(self.culprit);
+        test.sort_unstable();
+        Some(test)
+    }
+
+    fn record(&mut self, tested: &[usize], behavior: Behavior) {
+        let fixers: Vec<usize> = tested.iter().copied().filter(|&i| i != self.culprit).collect();
+        let swapped = match behavior {
+            Behavior::Dominant => Behavior::Recessive,
+            Behavior::Recessive => Behavior::Dominant,
+            other => other,
+        };
+        self.ddmin.record(&fixers, swapped);
+    }
+
+    fn report(&self) -> Report {
+        Report::default()
+    }
+}
+
+/// The built-in strategy, splitting dominant groups in half.
+impl<T: Stateful> Strategy for Bisection<T> {
+    fn next_test(&self) -> Option<Vec<usize>> {
+        self.next_group().map(|g| g.into_iter().collect())
+    }
+
+    fn record(&mut self, tested: &[usize], behavior: Behavior) {
+        Bisection::record(self, &Group::from_indices(tested.iter().copied()), behavior);
+    }
+
+    fn report(&self) -> Report {
+        Bisection::report(self)
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use std::{cell::Cell, rc::Rc};
 
     // useful macro to have
     macro_rules! test_assert_eq {
@@ -311,20 +1808,510 @@ mod tests {
     fn group_split_behavior() {
         let mut jef = Group::new(7, 8);
         jef.behavior = Behavior::Recessive;
+        let mut seven = Group::new(7, 7);
+        seven.behavior = Behavior::Recessive;
+        let mut eight = Group::new(8, 8);
+        eight.behavior = Behavior::Recessive;
+        assert_eq!(jef.split(), (seven, eight))
+    }
+
+    /// Either kind of group should work the same in a bisection.
+    #[test]
+    fn run_index_groups() {
+        let mut bisection = setup(8);
+        bisection.groups = vec![
+            Group::from_indices([0, 3, 6]),
+            Group::new(7, 7),
+            Group::from_indices([1, 2, 4, 5]),
+        ];
+        let report = bisection.run(any_of(&[3, 6])).unwrap();
+        assert_eq!(report.culprits, vec![3, 6]);
+        bisection.isolate(&Group::new(0, 0)).unwrap();
+        bisection.set_group_state(&Group::from_indices([2, 5]), State::Enabled).unwrap();
+        assert_eq!(bisection.enabled(), vec![0, 2, 5]);
+    }
+
+    /// A [Stateful] that only keeps track of its own state.
+    pub(crate) struct Dummy(Cell<State>);
+
+    impl Stateful for Dummy {
+        fn set_state(&self, state: &State) -> Result<(), Error> {
+            self.0.set(*state);
+            Ok(())
+        }
+
+        fn state(&self) -> State {
+            self.0.get()
+        }
+    }
+
+    pub(crate) fn setup(n: usize) -> Bisection<Dummy> {
+        Bisection::new((0..n).map(|_| Dummy(Cell::new(State::Enabled))).collect())
+    }
+
+    /// A test that's dominant if any of `culprits` are enabled.
+    pub(crate) fn any_of(culprits: &[usize]) -> impl FnMut(&[usize]) -> Behavior + '_ {
+        move |enabled| match culprits.iter().any(|i| enabled.contains(i)) {
+            true => Behavior::Dominant,
+            false => Behavior::Recessive,
+        }
+    }
+
+    #[test]
+    fn run_finds_culprits() {
+        for culprits in [vec![], vec![0], vec![6], vec![3, 4], vec![0, 1, 2, 3, 4, 5, 6]] {
+            let mut bisection = setup(7);
+            assert_eq!(bisection.run(any_of(&culprits)).unwrap().culprits, culprits);
+            assert!(bisection.is_done());
+        }
+    }
+
+    /// Results can be recorded in any order.
+    #[test]
+    fn record_out_of_order() {
+        let mut bisection = setup(4);
+        bisection.record(&Group::new(0, 3), Behavior::Dominant);
+        bisection.record(&Group::new(2, 3), Behavior::Dominant);
+        bisection.record(&Group::new(3, 3), Behavior::Recessive);
+        assert!(!bisection.is_done());
+        bisection.record(&Group::new(2, 2), Behavior::Dominant);
+        bisection.record(&Group::new(0, 1), Behavior::Recessive);
+        assert!(bisection.is_done());
+        assert_eq!(bisection.culprits(), vec![2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn record_unknown_group() {
+        let mut bisection = setup(4);
+        bisection.record(&Group::new(0, 1), Behavior::Dominant);
+    }
+
+    /// A culprit that only crashes every third run should still be found.
+    #[test]
+    fn run_flaky() {
+        let mut bisection = setup(8);
+        bisection.set_policy(Policy::AnyOf { runs: 3, fail_rate: 0.5 });
+        let mut culprit = any_of(&[3]);
+        let mut runs = 0;
+        let report = bisection.run(|enabled: &[usize]| {
+            runs += 1;
+            match runs % 3 {
+                0 => culprit(enabled),
+                _ => Behavior::Recessive,
+            }
+        }).unwrap();
+        assert_eq!(report.culprits, vec![3]);
+        assert_eq!(report.confidence[&3], 1.0);
+    }
+
+    /// Two objects that only fail together should be found together.
+    #[test]
+    fn run_interaction() {
+        let mut bisection = setup(8);
+        let mut culprit = any_of(&[6]);
+        let report = bisection.run(|enabled: &[usize]| {
+            match enabled.contains(&1) && enabled.contains(&2) {
+                true => Behavior::Dominant,
+                false => culprit(enabled),
+            }
+        }).unwrap();
+        assert_eq!(report.culprits, vec![6]);
+        assert_eq!(report.interactions, vec![vec![1, 2]]);
+        assert!(bisection.is_done());
+    }
+
+    #[test]
+    fn minimize_recessive() {
+        let mut bisection = setup(8);
+        assert_eq!(bisection.minimize(|_: &[usize]| Behavior::Recessive).unwrap(), None);
+        assert_eq!(bisection.minimize(any_of(&[2, 3])).unwrap(), Some(vec![2]));
+    }
+
+    #[test]
+    fn run_dependencies() {
+        let mut bisection = setup(8);
+        // 5 needs 1, which needs 0
+        bisection.require(5, 1);
+        bisection.require(1, 0);
+        assert_eq!(bisection.closure(&[5, 6]), vec![0, 1, 5, 6]);
+        let mut culprit = any_of(&[1]);
+        let report = bisection.run(|enabled: &[usize]| {
+            // missing a dependency would be dominant for the wrong reasons
+            assert!(!enabled.contains(&5) || enabled.contains(&1));
+            assert!(!enabled.contains(&1) || enabled.contains(&0));
+            culprit(enabled)
+        }).unwrap();
+        assert_eq!(report.culprits, vec![1]);
+        assert_eq!(report.implicated, BTreeMap::from([(5, vec![1])]));
+    }
+
+    #[test]
+    fn run_conflicts() {
+        let mut bisection = setup(8);
+        bisection.conflict(2, 5);
+        // 7 needs both sides of the conflict, so it can't be tested at all
+        bisection.require(7, 2);
+        bisection.require(7, 5);
+        let mut culprit = any_of(&[5]);
+        let report = bisection.run(|enabled: &[usize]| {
+            assert!(!(enabled.contains(&2) && enabled.contains(&5)));
+            culprit(enabled)
+        }).unwrap();
+        assert_eq!(report.culprits, vec![5]);
+        assert_eq!(report.indeterminate, vec![7]);
+    }
+
+    #[test]
+    fn run_pinned() {
+        let mut bisection = setup(8);
+        // 0 is the loader, and 7 is already known to be broken
+        bisection.pin(0, State::Enabled);
+        bisection.pin(7, State::Disabled);
+        // 5 can't work without 7
+        bisection.require(5, 7);
+        let mut culprit = any_of(&[3]);
+        let report = bisection.run(|enabled: &[usize]| {
+            assert!(enabled.contains(&0));
+            assert!(!enabled.contains(&7));
+            culprit(enabled)
+        }).unwrap();
+        assert!(bisection.history().iter().all(|r| !r.group.contains(0) && !r.group.contains(7)));
+        assert_eq!(report.culprits, vec![3]);
+        assert_eq!(report.indeterminate, vec![5]);
+        assert_eq!(report.pinned, BTreeMap::from([(0, State::Enabled), (7, State::Disabled)]));
+    }
+
+    #[test]
+    fn run_seeded() {
+        let mut bisection = setup(8);
+        bisection.seed(1, Behavior::Recessive);
+        bisection.seed(2, Behavior::Recessive);
+        bisection.seed(6, Behavior::Dominant);
+        let mut culprit = any_of(&[4, 6]);
+        let report = bisection.run(|enabled: &[usize]| {
+            assert!(![1, 2, 6].iter().any(|i| enabled.contains(i)));
+            culprit(enabled)
+        }).unwrap();
+        assert_eq!(report.culprits, vec![4, 6]);
+        assert!(report.wrong_seeds.is_empty());
+        // verified too, but without testing the seeds
+        let verification = report.verification.unwrap();
+        assert_eq!(verification.culprits, BTreeMap::from([(4, Behavior::Dominant)]));
+        assert!(verification.is_consistent());
+    }
+
+    #[test]
+    fn run_reverify_seeds() {
+        let mut bisection = setup(8);
+        bisection.seed(1, Behavior::Recessive);
+        bisection.seed(2, Behavior::Recessive);
+        bisection.seed(6, Behavior::Dominant);
+        bisection.set_reverify_seeds(true);
+        // turns out 2 was broken, and 6 was fine
+        let report = bisection.run(any_of(&[2, 4])).unwrap();
+        assert_eq!(report.culprits, vec![2, 4]);
+        assert_eq!(report.wrong_seeds, vec![2, 6]);
+    }
+
+    #[test]
+    fn run_inference() {
+        let count = |inference: bool| {
+            let mut bisection = setup(16);
+            bisection.set_inference(inference);
+            let mut runs = 0;
+            let mut culprit = any_of(&[13]);
+            let report = bisection.run(|enabled: &[usize]| {
+                runs += 1;
+                culprit(enabled)
+            }).unwrap();
+            assert_eq!(report.culprits, vec![13]);
+            let inferred = report.history.iter().filter(|r| r.source == Source::Inferred).count();
+            (runs, inferred)
+        };
+        let (measured, _) = count(false);
+        let (inferred_runs, inferred) = count(true);
+        assert!(inferred > 0);
+        assert_eq!(inferred_runs + inferred, measured);
+    }
+
+    /// An inferred culprit is only as sure as what it was inferred from.
+    #[test]
+    fn inferred_confidence() {
+        let mut bisection = setup(2);
+        bisection.set_inference(true);
+        let verdict = |behavior, confidence| Verdict { behavior, confidence, runs: 3 };
+        bisection.record_verdict(&Group::new(0, 1), verdict(Behavior::Dominant, 0.9));
+        let half = bisection.next_group().unwrap();
+        bisection.record_verdict(&half, verdict(Behavior::Recessive, 0.5));
+        let report = bisection.report();
+        assert_eq!(report.culprits.len(), 1);
+        assert!(report.history.iter().any(|r| r.source == Source::Inferred));
+        assert!((report.confidence[&report.culprits[0]] - 0.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn run_verify() {
+        let mut bisection = setup(8);
+        let report = bisection.run(any_of(&[2, 5])).unwrap();
+        assert!(report.verification.unwrap().is_consistent());
+        // everything but 2 crashes, which the search never tries
+        let mut bisection = setup(8);
+        let report = bisection.run(|enabled: &[usize]| match enabled.len() {
+            7 => Behavior::Dominant,
+            _ => any_of(&[2])(enabled),
+        }).unwrap();
+        let verification = report.verification.unwrap();
+        assert_eq!(verification.culprits, BTreeMap::from([(2, Behavior::Dominant)]));
+        assert_eq!(verification.rest, Some(Behavior::Dominant));
+        assert!(!verification.is_consistent());
+        // the rest can't be tested together, which isn't a failure
+        let mut bisection = setup(8);
+        bisection.conflict(0, 1);
+        let report = bisection.run(any_of(&[5])).unwrap();
+        let verification = report.verification.unwrap();
+        assert_eq!(verification.rest, None);
+        assert!(verification.is_consistent());
+    }
+
+    #[test]
+    fn contradictions() {
+        let mut bisection = setup(8);
+        // a dominant parent with two recessive halves is fine, it's an
+        // interaction
+        bisection.record(&Group::new(0, 7), Behavior::Dominant);
+        bisection.record(&Group::new(0, 3), Behavior::Recessive);
+        bisection.record(&Group::new(4, 7), Behavior::Recessive);
+        assert!(bisection.contradictions().is_empty());
+        // but a dominant group inside a recessive one isn't
+        bisection.groups.push(Group::new(2, 3));
+        bisection.record(&Group::new(2, 3), Behavior::Dominant);
+        let contradiction = bisection.contradictions()[0].clone();
+        assert_eq!(bisection.contradictions().len(), 1);
+        assert!(contradiction.recessive.group.same_indices(&Group::new(0, 3)));
+        assert!(contradiction.dominant.group.same_indices(&Group::new(2, 3)));
+        // it was flaky, so it's fine the second time around
         assert_eq!(
-            jef.split(),
-            (
-                Group {
-                    from: 7,
-                    to: 7,
-                    behavior: Behavior::Recessive
-                },
-                Group {
-                    from: 8,
-                    to: 8,
-                    behavior: Behavior::Recessive
+            bisection.retest(&contradiction, any_of(&[])).unwrap(),
+            (Behavior::Recessive, Behavior::Recessive)
+        );
+    }
+
+    #[test]
+    fn run_masking() {
+        // 2 crashes unless 6 is there to patch it
+        let masked = |enabled: &[usize]| match enabled.contains(&2) && !enabled.contains(&6) {
+            true => Behavior::Dominant,
+            false => Behavior::Recessive,
+        };
+        let mut bisection = setup(8);
+        assert_eq!(bisection.run(masked).unwrap().culprits, vec![]);
+        let mut bisection = setup(8);
+        bisection.set_masking(true);
+        let report = bisection.run(masked).unwrap();
+        assert_eq!(report.culprits, vec![]);
+        assert_eq!(report.masks, vec![Mask { culprit: 2, fixers: vec![6] }]);
+        assert!(!report.contradictions.is_empty());
+        // a culprit with no fix is still a culprit
+        let mut bisection = setup(8);
+        bisection.set_masking(true);
+        let mut culprit = any_of(&[4]);
+        let report = bisection.run(|enabled: &[usize]| match enabled.contains(&1) && !enabled.contains(&3) {
+            true => Behavior::Dominant,
+            false => culprit(enabled),
+        }).unwrap();
+        assert_eq!(report.culprits, vec![4]);
+        assert_eq!(report.masks, vec![Mask { culprit: 1, fixers: vec![3] }]);
+    }
+
+    /// Crashes on the first enabled culprit, without saying anything about
+    /// the rest.
+    struct FirstCrash {
+        culprits: Vec<usize>,
+        crashed: Option<usize>,
+    }
+
+    impl Tester for FirstCrash {
+        fn test(&mut self, enabled: &[usize]) -> Behavior {
+            self.crashed = enabled.iter().copied().find(|i| self.culprits.contains(i));
+            match self.crashed {
+                Some(_) => Behavior::Dominant,
+                None => Behavior::Recessive,
+            }
+        }
+
+        fn signature(&self) -> Option<String> {
+            self.crashed.map(|i| format!("crashed loading {i}"))
+        }
+    }
+
+    #[test]
+    fn peel() {
+        let mut bisection = setup(8);
+        let report = bisection.peel(FirstCrash {
+            culprits: vec![6, 1, 3],
+            crashed: None,
+        }).unwrap();
+        let peeled: Vec<_> = report.peeled.iter().map(|p| (p.culprits.clone(), p.signature.clone())).collect();
+        assert_eq!(
+            peeled,
+            vec![
+                (vec![1], Some("crashed loading 1".to_string())),
+                (vec![3], Some("crashed loading 3".to_string())),
+                (vec![6], Some("crashed loading 6".to_string())),
+            ]
+        );
+        assert_eq!(report.pinned.len(), 3);
+
+        // no telling which half it's in, so nothing gets pinned
+        let mut bisection = setup(4);
+        let report = bisection
+            .peel(|enabled: &[usize]| match enabled.len() {
+                4 => Behavior::Dominant,
+                _ => Behavior::Indeterminate,
+            })
+            .unwrap();
+        assert!(report.peeled.is_empty());
+        assert!(report.pinned.is_empty());
+        assert_eq!(report.indeterminate, vec![0, 1, 2, 3]);
+    }
+
+    /// A [Stateful] that fails to change state the first few times.
+    struct Locked {
+        state: Cell<State>,
+        failures: Cell<usize>,
+    }
+
+    impl Stateful for Locked {
+        fn set_state(&self, state: &State) -> Result<(), Error> {
+            if self.failures.get() > 0 {
+                self.failures.set(self.failures.get() - 1);
+                return Err(Error::Other("locked".to_string()));
+            }
+            self.state.set(*state);
+            Ok(())
+        }
+
+        fn state(&self) -> State {
+            self.state.get()
+        }
+    }
+
+    #[test]
+    fn run_state_errors() {
+        let locked = |failures: usize| {
+            let mut objects: Vec<Locked> = (0..4)
+                .map(|_| Locked {
+                    state: Cell::new(State::Enabled),
+                    failures: Cell::new(0),
+                })
+                .collect();
+            objects[2].failures.set(failures);
+            Bisection::new(objects)
+        };
+        let mut bisection = locked(1);
+        match bisection.run(any_of(&[1])) {
+            Err(Error::State { index: 2, state: State::Disabled, .. }) => {}
+            result => panic!("expected object 2 to fail, got {result:?}"),
+        }
+        // nothing was recorded, so it can carry on once the lock is gone
+        let report = bisection.run(any_of(&[1])).unwrap();
+        assert_eq!(report.culprits, vec![1]);
+
+        let mut bisection = locked(2);
+        bisection.set_retries(2, Duration::ZERO);
+        assert_eq!(bisection.run(any_of(&[1])).unwrap().culprits, vec![1]);
+        assert!(matches!(
+            bisection.set_group_state(&Group::new(4, 4), State::Enabled),
+            Err(Error::NoObject(4))
+        ));
+    }
+
+    /// A [Stateful] whose real state can be changed behind its back.
+    struct Shared {
+        index: usize,
+        cached: Cell<State>,
+        real: Rc<Vec<Cell<State>>>,
+    }
+
+    impl Stateful for Shared {
+        fn set_state(&self, state: &State) -> Result<(), Error> {
+            self.cached.set(*state);
+            self.real[self.index].set(*state);
+            Ok(())
+        }
+
+        fn state(&self) -> State {
+            self.cached.get()
+        }
+
+        fn verify_state(&self) -> Option<State> {
+            Some(self.real[self.index].get())
+        }
+    }
+
+    /// A launcher that re-enables 0 after every test, which is also the only
+    /// culprit.
+    #[test]
+    fn run_drift() {
+        let drifting = |on_drift: OnDrift| {
+            let real: Rc<Vec<Cell<State>>> = Rc::new((0..8).map(|_| Cell::new(State::Enabled)).collect());
+            let objects = (0..8)
+                .map(|index| Shared {
+                    index,
+                    cached: Cell::new(State::Enabled),
+                    real: real.clone(),
+                })
+                .collect();
+            let mut bisection = Bisection::new(objects);
+            bisection.set_on_drift(on_drift);
+            bisection.run(move |_: &[usize]| {
+                let crashed = real[0].get() == State::Enabled;
+                real[0].set(State::Enabled);
+                match crashed {
+                    true => Behavior::Dominant,
+                    false => Behavior::Recessive,
                 }
-            )
-        )
+            })
+        };
+        let report = drifting(OnDrift::Repair).unwrap();
+        assert_eq!(report.culprits, vec![0]);
+        assert!(!report.drift.is_empty());
+        assert!(report.drift.iter().all(|d| d.index == 0 && d.repaired));
+        assert!(report.verification.unwrap().is_consistent());
+
+        // left alone, 0 ends up in tests it shouldn't be in
+        let report = drifting(OnDrift::Warn).unwrap();
+        assert!(!report.drift.is_empty());
+        assert!(report.drift.iter().all(|d| d.index == 0 && !d.repaired));
+        assert!(!report.verification.unwrap().is_consistent());
+
+        assert!(matches!(
+            drifting(OnDrift::Abort),
+            Err(Error::Drift {
+                index: 0,
+                expected: State::Disabled,
+                found: State::Enabled
+            })
+        ));
+    }
+
+    #[test]
+    fn run_empty() {
+        let mut bisection = setup(0);
+        assert_eq!(bisection.run(|_: &[usize]| unreachable!()).unwrap(), Report::default());
+    }
+
+    /// Objects that can't be tested shouldn't hide the ones that can.
+    #[test]
+    fn run_indeterminate() {
+        let mut bisection = setup(8);
+        let mut culprit = any_of(&[6]);
+        let report = bisection.run(|enabled: &[usize]| match enabled.contains(&1) {
+            true => Behavior::Indeterminate,
+            false => culprit(enabled),
+        }).unwrap();
+        assert_eq!(report.culprits, vec![6]);
+        assert_eq!(report.indeterminate, vec![1]);
     }
 }
@@ -0,0 +1,210 @@
+//! Deciding on a [Behavior] when a test is flaky.
+//!
+//! Some failures are intermittent, so a single recessive run doesn't prove
+//! much. A [Policy] decides how many times to run a test, and how sure we can
+//! be of the result, which is handed back as a [Verdict].
+
+use crate::{bisection::Behavior, tester::Tester};
+
+/// How to run a test that might be flaky.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Policy {
+    /// Run every test once, and trust the result.
+    #[default]
+    Once,
+    /// Run up to `runs` times, and call it dominant as soon as any run is.
+    ///
+    /// `fail_rate` is how often a dominant configuration is expected to
+    /// actually fail on a single run, and is only used to work out how
+    /// confident a recessive verdict is.
+    AnyOf { runs: usize, fail_rate: f64 },
+    /// Run exactly `runs` times, and go with the majority. Ties are
+    /// [Behavior::Indeterminate].
+    Majority { runs: usize },
+    /// Wald's sequential probability ratio test.
+    ///
+    /// Keep running until we can tell a configuration that fails with
+    /// probability `recessive_rate` (recessive, failing only by chance) apart
+    /// from one that fails with probability `dominant_rate` (dominant), with
+    /// an error rate of `error` both ways. Gives up after `max_runs`, going
+    /// with whichever is more likely at that point.
+    Sprt {
+        recessive_rate: f64,
+        dominant_rate: f64,
+        error: f64,
+        max_runs: usize,
+    },
+}
+
+/// The result of testing a configuration under some [Policy].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Verdict {
+    pub behavior: Behavior,
+    /// How likely it is that `behavior` is right, from 0 to 1.
+    pub confidence: f64,
+    /// How many times the test was ran.
+    pub runs: usize,
+}
+
+impl Verdict {
+    /// A verdict we're completely sure of, from a single run.
+    pub fn certain(behavior: Behavior) -> Self {
+        Self {
+            behavior,
+            confidence: 1.0,
+            runs: 1,
+        }
+    }
+}
+
+impl Policy {
+    /// Run `tester` as many times as this policy needs, and decide on a
+    /// [Verdict].
+    ///
+    /// Indeterminate runs count towards the number of runs, but aren't
+    /// evidence either way. If no run gave any evidence, the verdict is
+    /// [Behavior::Indeterminate].
+    ///
+    /// # Examples
+    /// ```
+    /// # use halfwit::bisection::Behavior::*;
+    /// # use halfwit::confidence::Policy;
+    /// let policy = Policy::AnyOf { runs: 5, fail_rate: 0.5 };
+    /// // crashes on the third try
+    /// let mut tries = 0;
+    /// let verdict = policy.judge(&mut |_: &[usize]| {
+    ///     tries += 1;
+    ///     if tries == 3 { Dominant } else { Recessive }
+    /// }, &[]);
+    /// assert_eq!(verdict.behavior, Dominant);
+    /// assert_eq!(verdict.runs, 3);
+    /// ```
+    pub fn judge<X: Tester>(&self, tester: &mut X, enabled: &[usize]) -> Verdict {
+        let (mut dominant, mut recessive, mut runs) = (0, 0, 0);
+        loop {
+            match tester.test(enabled) {
+                Behavior::Dominant => dominant += 1,
+                Behavior::Recessive => recessive += 1,
+                Behavior::Indeterminate => {},
+                Behavior::Unknown => panic!("test returned Behavior::Unknown"),
+            }
+            runs += 1;
+            if let Some((behavior, confidence)) = self.decide(dominant, recessive, runs) {
+                return Verdict {
+                    behavior,
+                    confidence,
+                    runs,
+                };
+            }
+        }
+    }
+
+    /// Decide on a behavior and confidence from the runs so far, or `None` if
+    /// more runs are needed.
+    fn decide(&self, dominant: usize, recessive: usize, runs: usize) -> Option<(Behavior, f64)> {
+        use Behavior::*;
+        let indeterminate = Some((Indeterminate, 0.0));
+        match *self {
+            Policy::Once => match (dominant, recessive) {
+                (0, 0) => indeterminate,
+                (0, _) => Some((Recessive, 1.0)),
+                _ => Some((Dominant, 1.0)),
+            },
+            Policy::AnyOf { runs: max, fail_rate } => match (dominant, recessive) {
+                (1.., _) => Some((Dominant, 1.0)),
+                _ if runs < max.max(1) => None,
+                (_, 0) => indeterminate,
+                // chance a dominant configuration would have failed by now
+                (_, r) => Some((Recessive, 1.0 - (1.0 - fail_rate).powi(r as i32))),
+            },
+            Policy::Majority { runs: max } => {
+                let total = (dominant + recessive) as f64;
+                match dominant.cmp(&recessive) {
+                    _ if runs < max.max(1) => None,
+                    std::cmp::Ordering::Equal => indeterminate,
+                    std::cmp::Ordering::Greater => Some((Dominant, dominant as f64 / total)),
+                    std::cmp::Ordering::Less => Some((Recessive, recessive as f64 / total)),
+                }
+            },
+            Policy::Sprt {
+                recessive_rate: p0,
+                dominant_rate: p1,
+                error,
+                max_runs,
+            } => {
+                // log likelihood ratio of dominant over recessive
+                let llr = dominant as f64 * (p1 / p0).ln()
+                    + recessive as f64 * ((1.0 - p1) / (1.0 - p0)).ln();
+                let upper = ((1.0 - error) / error).ln();
+                // posterior, assuming both were equally likely to begin with
+                let posterior = 1.0 / (1.0 + (-llr).exp());
+                if llr >= upper {
+                    Some((Dominant, posterior))
+                } else if llr <= -upper {
+                    Some((Recessive, 1.0 - posterior))
+                } else if runs < max_runs.max(1) {
+                    None
+                } else if dominant + recessive == 0 || llr == 0.0 {
+                    indeterminate
+                } else if llr > 0.0 {
+                    Some((Dominant, posterior))
+                } else {
+                    Some((Recessive, 1.0 - posterior))
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Behavior::*;
+
+    /// Returns each of `results` in turn, forever.
+    fn sequence(results: &[Behavior]) -> impl FnMut(&[usize]) -> Behavior + '_ {
+        let mut i = 0;
+        move |_| {
+            i += 1;
+            results[(i - 1) % results.len()]
+        }
+    }
+
+    #[test]
+    fn any_of_confidence() {
+        let policy = Policy::AnyOf { runs: 3, fail_rate: 0.5 };
+        let verdict = policy.judge(&mut sequence(&[Recessive]), &[]);
+        assert_eq!(verdict.behavior, Recessive);
+        assert_eq!(verdict.runs, 3);
+        assert_eq!(verdict.confidence, 0.875);
+    }
+
+    #[test]
+    fn majority_tie() {
+        let policy = Policy::Majority { runs: 4 };
+        let verdict = policy.judge(&mut sequence(&[Dominant, Recessive]), &[]);
+        assert_eq!(verdict.behavior, Indeterminate);
+        let verdict = policy.judge(&mut sequence(&[Dominant, Dominant, Recessive, Indeterminate]), &[]);
+        assert_eq!((verdict.behavior, verdict.runs), (Dominant, 4));
+        assert!((verdict.confidence - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sprt() {
+        let policy = Policy::Sprt {
+            recessive_rate: 0.01,
+            dominant_rate: 0.5,
+            error: 0.05,
+            max_runs: 100,
+        };
+        // a failure is very unlikely from a recessive configuration
+        let verdict = policy.judge(&mut sequence(&[Recessive, Dominant]), &[]);
+        assert_eq!(verdict.behavior, Dominant);
+        assert!(verdict.confidence >= 0.95);
+        // but a recessive configuration needs a few clean runs to be sure
+        let verdict = policy.judge(&mut sequence(&[Recessive]), &[]);
+        assert_eq!(verdict.behavior, Recessive);
+        assert!(verdict.runs > 1);
+        assert!(verdict.confidence >= 0.95);
+    }
+}
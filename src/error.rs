@@ -0,0 +1,76 @@
+//! Things that can go wrong outside of the search itself.
+
+use std::{fmt, io};
+
+use crate::bisection::State;
+
+/// Something went wrong with the objects being searched, rather than with the
+/// test.
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error, like a file that couldn't be renamed.
+    Io(io::Error),
+    /// Anything else, described for people.
+    Other(String),
+    /// There's no object with this index.
+    NoObject(usize),
+    /// Object `index` couldn't be set to `state`.
+    State {
+        index: usize,
+        state: State,
+        source: Box<Error>,
+    },
+    /// Object `index` should have been `expected`, but was really `found`,
+    /// see [Bisection::set_on_drift](crate::bisection::Bisection::set_on_drift).
+    Drift {
+        index: usize,
+        expected: State,
+        found: State,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{e}"),
+            Error::Other(message) => write!(f, "{message}"),
+            Error::NoObject(index) => write!(f, "there's no object {index}"),
+            Error::State { index, state, source } => {
+                let verb = match state {
+                    State::Enabled => "enable",
+                    State::Disabled => "disable",
+                };
+                write!(f, "couldn't {verb} object {index}: {source}")
+            }
+            Error::Drift { index, expected, found } => write!(
+                f,
+                "object {index} should be {}, but it's {}",
+                adjective(*expected),
+                adjective(*found)
+            ),
+        }
+    }
+}
+
+fn adjective(state: State) -> &'static str {
+    match state {
+        State::Enabled => "enabled",
+        State::Disabled => "disabled",
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::State { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
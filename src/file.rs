@@ -0,0 +1,189 @@
+//! Files that can be enabled and disabled.
+//!
+//! There's more than one way to disable a file, since programs differ in what
+//! they'll load:
+//! - [File] renames it to end in `.disabled`, which is what most mod
+//!   launchers (MultiMC, Prism, ...) do.
+//! - [Stashed] moves it into a stash directory next to the real one.
+//! - [Linked] leaves it alone, and keeps a directory of symlinks to only the
+//!   enabled files, for the program to look at instead.
+//! - [Unreadable] takes away its read permissions (unix only).
+
+mod link;
+#[cfg(unix)]
+mod permissions;
+mod rename;
+mod stash;
+
+pub use link::Linked;
+#[cfg(unix)]
+pub use permissions::Unreadable;
+pub use rename::File;
+pub use stash::Stashed;
+
+use std::{
+    cell::Cell,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    bisection::{State, Stateful},
+    error::Error,
+};
+
+/// A file that can be enabled and disabled.
+///
+/// A failed state change leaves [Stateful::state] as it was.
+pub trait Toggle: Stateful {
+    /// Where the file is when it's enabled.
+    fn path(&self) -> &Path;
+
+    /// The file name, for showing to people.
+    fn name(&self) -> String {
+        match self.path().file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => self.path().display().to_string(),
+        }
+    }
+}
+
+/// The cached state of a file, which only changes if changing the file worked.
+#[derive(Debug)]
+struct Tracked {
+    state: Cell<State>,
+}
+
+impl Tracked {
+    fn new(state: State) -> Self {
+        Self {
+            state: Cell::new(state),
+        }
+    }
+
+    fn get(&self) -> State {
+        self.state.get()
+    }
+
+    /// Change to `state` using `change`.
+    ///
+    /// `change` is called even if the cached state is already `state`, since
+    /// the file might have changed behind our back, so it should do nothing if
+    /// the file really is there already.
+    fn set(&self, state: State, change: impl FnOnce() -> io::Result<()>) -> Result<(), Error> {
+        change()?;
+        self.state.set(state);
+        Ok(())
+    }
+}
+
+/// Whether anything is at `path`, including broken symlinks.
+fn exists(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+/// Rename `from` to `to`, unless there's something at `to` already.
+///
+/// If there's nothing at `from` and something at `to`, it's already been
+/// renamed, and nothing happens.
+fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    if !exists(from) && exists(to) {
+        return Ok(());
+    }
+    if exists(to) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    fs::rename(from, to)
+}
+
+/// Enabled if only `enabled` exists, disabled if only `disabled` exists, and
+/// `None` otherwise.
+fn which_exists(enabled: &Path, disabled: &Path) -> Option<State> {
+    match (exists(enabled), exists(disabled)) {
+        (true, false) => Some(State::Enabled),
+        (false, true) => Some(State::Disabled),
+        _ => None,
+    }
+}
+
+/// `<parent>/<name><suffix>` for `dir` at `<parent>/<name>`, however `dir`
+/// is written (`mods/`, `.`, ...), so that it never ends up inside `dir`.
+fn sibling(dir: &Path, suffix: &str) -> io::Result<PathBuf> {
+    let dir = fs::canonicalize(dir)?;
+    match (dir.parent(), dir.file_name()) {
+        (Some(parent), Some(name)) => Ok(parent.join(with_suffix(Path::new(name), suffix))),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has nowhere to put a {suffix} directory next to it", dir.display()),
+        )),
+    }
+}
+
+/// The first of `<base><suffix>`, `<base>.1<suffix>`, ... that isn't taken,
+/// so that a file can always be moved out of the way of what's already there.
+fn free_path(base: &Path, suffix: &str) -> PathBuf {
+    let mut free = with_suffix(base, suffix);
+    let mut n = 1;
+    while exists(&free) {
+        free = with_suffix(base, &format!(".{n}{suffix}"));
+        n += 1;
+    }
+    free
+}
+
+/// The last of `<base><suffix>`, `<base>.1<suffix>`, ... in a row that
+/// exists, or `None` if not even the first one does, in which case nothing
+/// numbered could have come from [free_path].
+fn last_copy(base: &Path, suffix: &str) -> Option<PathBuf> {
+    let mut copy = Some(with_suffix(base, suffix)).filter(|p| exists(p))?;
+    let mut n = 1;
+    loop {
+        let numbered = with_suffix(base, &format!(".{n}{suffix}"));
+        if !exists(&numbered) {
+            return Some(copy);
+        }
+        copy = numbered;
+        n += 1;
+    }
+}
+
+/// `("foo.jar", 2)` for `foo.jar.2`, if the number looks like [free_path]
+/// put it there. Whether it really did is up to the caller.
+fn split_number(name: &str) -> Option<(&str, usize)> {
+    let (rest, n) = name.rsplit_once('.')?;
+    match !n.is_empty() && !n.starts_with('0') && n.bytes().all(|b| b.is_ascii_digit()) {
+        true => Some((rest, n.parse().ok()?)),
+        false => None,
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Every regular file in `dir`.
+fn files_in(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    Ok(paths)
+}
+
+/// A fresh, empty directory for a test.
+#[cfg(test)]
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("halfwit-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
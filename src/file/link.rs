@@ -0,0 +1,133 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{exists, files_in, sibling, Toggle, Tracked};
+use crate::{
+    bisection::{State, Stateful},
+    error::Error,
+};
+
+/// A file that's enabled by having a symlink to it in a separate directory
+/// (the farm), and disabled by removing that symlink.
+///
+/// The file itself is never touched, the program being tested has to be
+/// pointed at the farm instead.
+#[derive(Debug)]
+pub struct Linked {
+    path: PathBuf,
+    link: PathBuf,
+    state: Tracked,
+}
+
+impl Linked {
+    /// A file at `path`, with its symlink in `farm`. It's enabled if the
+    /// symlink is already there.
+    pub fn new(path: impl Into<PathBuf>, farm: impl AsRef<Path>) -> Self {
+        let path = path.into();
+        let link = farm.as_ref().join(path.file_name().unwrap_or_default());
+        let state = match exists(&link) {
+            true => State::Enabled,
+            false => State::Disabled,
+        };
+        Self {
+            path,
+            link,
+            state: Tracked::new(state),
+        }
+    }
+
+    /// Every file in `dir`, sorted by name, with their symlinks in `farm`,
+    /// which is created if it doesn't exist. Directories are skipped.
+    pub fn discover(dir: impl AsRef<Path>, farm: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        // the symlinks have to work from wherever the farm is
+        let dir = fs::canonicalize(dir)?;
+        fs::create_dir_all(&farm)?;
+        let mut paths = files_in(&dir)?;
+        paths.sort();
+        Ok(paths.into_iter().map(|path| Self::new(path, &farm)).collect())
+    }
+
+    /// The default farm for files in `dir`, which has to exist. It goes next
+    /// to `dir`, named `<dir>.halfwit-farm`.
+    pub fn farm_for(dir: &Path) -> io::Result<PathBuf> {
+        sibling(dir, ".halfwit-farm")
+    }
+
+    /// Where the symlink is when the file is enabled.
+    pub fn link_path(&self) -> &Path {
+        &self.link
+    }
+}
+
+impl Toggle for Linked {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Stateful for Linked {
+    fn set_state(&self, state: &State) -> Result<(), Error> {
+        self.state.set(*state, || match (state, self.verify_state()) {
+            (State::Enabled, Some(State::Enabled)) | (State::Disabled, Some(State::Disabled)) => Ok(()),
+            (State::Enabled, _) => symlink(&self.path, &self.link),
+            (State::Disabled, Some(State::Enabled)) => fs::remove_file(&self.link),
+            (State::Disabled, None) => Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} isn't a symlink", self.link.display()),
+            )),
+        })
+    }
+
+    fn state(&self) -> State {
+        self.state.get()
+    }
+
+    fn verify_state(&self) -> Option<State> {
+        match fs::symlink_metadata(&self.link) {
+            Ok(metadata) if metadata.file_type().is_symlink() => Some(State::Enabled),
+            Ok(_) => None,
+            Err(_) => Some(State::Disabled),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(original, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "symlinks aren't supported here"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::temp_dir;
+
+    #[test]
+    fn toggle() {
+        let root = temp_dir("link");
+        let (dir, farm) = (root.join("mods"), root.join("farm"));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.jar"), "a").unwrap();
+        fs::write(dir.join("b.jar"), "b").unwrap();
+        let files = Linked::discover(&dir, &farm).unwrap();
+        assert!(files.iter().all(|f| f.state() == State::Disabled));
+        files[0].set_state(&State::Enabled).unwrap();
+        assert_eq!(fs::read_to_string(farm.join("a.jar")).unwrap(), "a");
+        assert!(!exists(&farm.join("b.jar")));
+        files[0].set_state(&State::Disabled).unwrap();
+        assert!(!exists(&farm.join("a.jar")));
+        assert!(dir.join("a.jar").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    fs, io,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use super::{files_in, Toggle, Tracked};
+use crate::{
+    bisection::{State, Stateful},
+    error::Error,
+};
+
+/// Every read bit, for the owner, group and others.
+const READ: u32 = 0o444;
+
+/// A file that gets its read permissions taken away when disabled.
+///
+/// Nothing gets moved around, which is handy when the program is picky about
+/// what's in its directory, but it won't stop a program running as root.
+#[derive(Debug)]
+pub struct Unreadable {
+    path: PathBuf,
+    /// The permissions to go back to when enabled, exactly as they were.
+    mode: u32,
+    state: Tracked,
+}
+
+impl Unreadable {
+    /// A file at `path`, which is disabled if nobody can read it.
+    ///
+    /// A file that starts out disabled has no permissions to go back to, so
+    /// it's made readable by its owner when enabled.
+    pub fn new(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mode = fs::metadata(&path)?.permissions().mode();
+        let (state, mode) = match mode & READ {
+            0 => (State::Disabled, mode | 0o400),
+            _ => (State::Enabled, mode),
+        };
+        Ok(Self {
+            path,
+            mode,
+            state: Tracked::new(state),
+        })
+    }
+
+    /// Every file in `dir`, sorted by name. Directories are skipped.
+    pub fn discover(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let mut paths = files_in(dir.as_ref())?;
+        paths.sort();
+        paths.into_iter().map(Self::new).collect()
+    }
+
+    fn set_mode(&self, mode: u32) -> io::Result<()> {
+        fs::set_permissions(&self.path, fs::Permissions::from_mode(mode))
+    }
+}
+
+impl Toggle for Unreadable {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Stateful for Unreadable {
+    fn set_state(&self, state: &State) -> Result<(), Error> {
+        self.state.set(*state, || match state {
+            State::Enabled => self.set_mode(self.mode),
+            State::Disabled => self.set_mode(self.mode & !READ),
+        })
+    }
+
+    fn state(&self) -> State {
+        self.state.get()
+    }
+
+    fn verify_state(&self) -> Option<State> {
+        let mode = fs::metadata(&self.path).ok()?.permissions().mode();
+        if mode == self.mode {
+            Some(State::Enabled)
+        } else if mode == self.mode & !READ {
+            Some(State::Disabled)
+        } else {
+            // something else changed them, which wasn't us
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::temp_dir;
+
+    fn mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn toggle() {
+        let dir = temp_dir("permissions");
+        let path = dir.join("a.jar");
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        let files = Unreadable::discover(&dir).unwrap();
+        files[0].set_state(&State::Disabled).unwrap();
+        assert_eq!(mode(&path), 0o200);
+        files[0].set_state(&State::Enabled).unwrap();
+        assert_eq!(mode(&path), 0o640);
+        assert_eq!(files[0].verify_state(), Some(State::Enabled));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use super::{exists, files_in, free_path, last_copy, rename, split_number, which_exists, with_suffix, Toggle, Tracked};
+use crate::{
+    bisection::{State, Stateful},
+    error::Error,
+};
+
+const SUFFIX: &str = ".disabled";
+
+/// A file that gets renamed to `<name>.disabled` when disabled.
+///
+/// A file is never renamed over another one. If `<name>.disabled` is already
+/// taken by something else, `<name>.1.disabled` is used instead, and so on.
+/// Those are still recognized as `<name>` later on, and win over the plain
+/// `<name>.disabled`, since they were only ever made because that was taken.
+#[derive(Debug)]
+pub struct File {
+    path: PathBuf,
+    disabled: PathBuf,
+    state: Tracked,
+}
+
+impl File {
+    /// A file that's enabled at `path`, or disabled next to it.
+    ///
+    /// If neither exists, the file is assumed to be enabled, and will fail to
+    /// be disabled.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match exists(&path) {
+            true => Self::enabled(path),
+            false => match last_copy(&path, SUFFIX) {
+                Some(disabled) => Self::disabled(path, disabled),
+                None => Self::enabled(path),
+            },
+        }
+    }
+
+    fn enabled(path: PathBuf) -> Self {
+        Self {
+            disabled: free_path(&path, SUFFIX),
+            path,
+            state: Tracked::new(State::Enabled),
+        }
+    }
+
+    fn disabled(path: PathBuf, disabled: PathBuf) -> Self {
+        Self {
+            path,
+            disabled,
+            state: Tracked::new(State::Disabled),
+        }
+    }
+
+    /// Every file in `dir`, sorted by name, whether it's enabled or not.
+    /// Directories are skipped.
+    ///
+    /// A disabled file that has an enabled file with the same name next to it
+    /// is skipped too, since it can't be enabled without replacing the other
+    /// one.
+    pub fn discover(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let mut enabled = Vec::new();
+        // the disabled copy with the highest number, for each name
+        let mut disabled: BTreeMap<PathBuf, (usize, PathBuf)> = BTreeMap::new();
+        for path in files_in(dir.as_ref())? {
+            match without_suffix(&path) {
+                Some((name, _)) if exists(&name) => {}
+                Some((name, n)) => {
+                    if disabled.get(&name).is_none_or(|(m, _)| n > *m) {
+                        disabled.insert(name, (n, path));
+                    }
+                }
+                None => enabled.push(path),
+            }
+        }
+        let mut files: Vec<Self> = enabled
+            .into_iter()
+            .map(Self::enabled)
+            .chain(disabled.into_iter().map(|(name, (_, path))| Self::disabled(name, path)))
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Where the file is when it's disabled.
+    pub fn disabled_path(&self) -> &Path {
+        &self.disabled
+    }
+}
+
+impl Toggle for File {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Stateful for File {
+    fn set_state(&self, state: &State) -> Result<(), Error> {
+        self.state.set(*state, || match state {
+            State::Enabled => rename(&self.disabled, &self.path),
+            State::Disabled => rename(&self.path, &self.disabled),
+        })
+    }
+
+    fn state(&self) -> State {
+        self.state.get()
+    }
+
+    fn verify_state(&self) -> Option<State> {
+        which_exists(&self.path, &self.disabled)
+    }
+}
+
+/// `foo.jar` for `foo.jar.disabled` or `foo.jar.<n>.disabled`, along with
+/// `n` (0 if there isn't one), or `None` if it isn't disabled.
+///
+/// A name is only taken to be numbered if [free_path] could have made it,
+/// because `foo.jar.disabled` or `foo.jar` was in the way. Otherwise
+/// `libfoo.so.3.disabled` is just `libfoo.so.3`, disabled.
+fn without_suffix(path: &Path) -> Option<(PathBuf, usize)> {
+    let name = path.to_str()?.strip_suffix(SUFFIX)?;
+    let numbered = |&(rest, _): &(&str, usize)| !exists(Path::new(name)) && was_in_the_way(Path::new(rest));
+    match split_number(name).filter(numbered) {
+        Some((rest, n)) => Some((PathBuf::from(rest), n)),
+        None => Some((PathBuf::from(name), 0)),
+    }
+}
+
+/// Whether disabling `path` would have needed a numbered name.
+fn was_in_the_way(path: &Path) -> bool {
+    exists(path) || exists(&with_suffix(path, SUFFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn toggle() {
+        let dir = temp_dir("rename");
+        fs::write(dir.join("a.jar"), "").unwrap();
+        fs::write(dir.join("b.jar.disabled"), "").unwrap();
+        let files = File::discover(&dir).unwrap();
+        assert_eq!(files.iter().map(File::name).collect::<Vec<_>>(), ["a.jar", "b.jar"]);
+        assert_eq!(files[1].state(), State::Disabled);
+        files[0].set_state(&State::Disabled).unwrap();
+        files[1].set_state(&State::Enabled).unwrap();
+        assert_eq!(files[0].state(), State::Disabled);
+        assert!(dir.join("a.jar.disabled").exists());
+        assert!(!dir.join("a.jar").exists());
+        assert!(dir.join("b.jar").exists());
+
+        // something enabled it again behind our back
+        fs::rename(dir.join("a.jar.disabled"), dir.join("a.jar")).unwrap();
+        assert_eq!(files[0].verify_state(), Some(State::Enabled));
+        files[0].set_state(&State::Disabled).unwrap();
+        assert_eq!(files[0].verify_state(), Some(State::Disabled));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Nothing should ever get renamed over something else.
+    #[test]
+    fn collisions() {
+        let dir = temp_dir("collisions");
+        fs::write(dir.join("a.jar"), "enabled").unwrap();
+        fs::write(dir.join("a.jar.disabled"), "stray").unwrap();
+        let files = File::discover(&dir).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].disabled_path(), dir.join("a.jar.1.disabled"));
+        files[0].set_state(&State::Disabled).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("a.jar.disabled")).unwrap(), "stray");
+
+        // something took the name back in the meantime
+        fs::write(dir.join("a.jar"), "new").unwrap();
+        match files[0].set_state(&State::Enabled) {
+            Err(Error::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::AlreadyExists),
+            result => panic!("expected a collision, got {result:?}"),
+        }
+        assert_eq!(files[0].state(), State::Disabled);
+        assert_eq!(fs::read_to_string(dir.join("a.jar")).unwrap(), "new");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// A file moved out of the way of a stray `.disabled` file should still be
+    /// found after an interrupted run, and not swapped with the stray one.
+    #[test]
+    fn rediscover_collisions() {
+        let dir = temp_dir("rediscover");
+        fs::write(dir.join("a.jar"), "real").unwrap();
+        fs::write(dir.join("a.jar.disabled"), "stray").unwrap();
+        File::discover(&dir).unwrap()[0].set_state(&State::Disabled).unwrap();
+        assert!(dir.join("a.jar.1.disabled").exists());
+
+        let files = File::discover(&dir).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name(), "a.jar");
+        assert_eq!(files[0].state(), State::Disabled);
+        assert_eq!(files[0].disabled_path(), dir.join("a.jar.1.disabled"));
+        assert_eq!(File::new(dir.join("a.jar")).disabled_path(), dir.join("a.jar.1.disabled"));
+        files[0].set_state(&State::Enabled).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("a.jar")).unwrap(), "real");
+        assert_eq!(fs::read_to_string(dir.join("a.jar.disabled")).unwrap(), "stray");
+        assert_eq!(File::discover(&dir).unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// A versioned file is just a file, not a numbered copy of another one.
+    #[test]
+    fn versioned_names() {
+        let dir = temp_dir("versioned");
+        fs::write(dir.join("libfoo.so.3.disabled"), "").unwrap();
+        fs::write(dir.join("mod-1.2.disabled"), "").unwrap();
+        let files = File::discover(&dir).unwrap();
+        assert_eq!(files.iter().map(File::name).collect::<Vec<_>>(), ["libfoo.so.3", "mod-1.2"]);
+        files[0].set_state(&State::Enabled).unwrap();
+        assert!(dir.join("libfoo.so.3").exists());
+        assert!(!dir.join("libfoo.so").exists());
+        assert_eq!(File::new(dir.join("libfoo.so")).state(), State::Enabled);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{exists, files_in, free_path, last_copy, rename, sibling, split_number, which_exists, Toggle, Tracked};
+use crate::{
+    bisection::{State, Stateful},
+    error::Error,
+};
+
+/// A file that gets moved into a stash directory when disabled, for programs
+/// that load every file they can find, whatever it's called.
+///
+/// The stash directory for `mods` is `mods.halfwit-stash`, right next to it,
+/// and it's created the first time something is disabled. If there's already
+/// something called `<name>` in the stash, `<name>.1` is used instead, and so
+/// on, the same as with [File](super::File).
+#[derive(Debug)]
+pub struct Stashed {
+    path: PathBuf,
+    stashed: PathBuf,
+    state: Tracked,
+}
+
+impl Stashed {
+    /// A file that's enabled at `path`, or disabled in `stash`.
+    pub fn new(path: impl Into<PathBuf>, stash: impl AsRef<Path>) -> Self {
+        let path = path.into();
+        let base = stash.as_ref().join(path.file_name().unwrap_or_default());
+        match (exists(&path), last_copy(&base, "")) {
+            (false, Some(stashed)) => Self::with(path, stashed, State::Disabled),
+            _ => Self::with(path, free_path(&base, ""), State::Enabled),
+        }
+    }
+
+    fn with(path: PathBuf, stashed: PathBuf, state: State) -> Self {
+        Self {
+            path,
+            stashed,
+            state: Tracked::new(state),
+        }
+    }
+
+    /// Every file in `dir` and its stash, sorted by name. Directories are
+    /// skipped.
+    ///
+    /// A stashed file that has an enabled file with the same name is skipped
+    /// too, since it can't be enabled without replacing the other one.
+    pub fn discover(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let dir = dir.as_ref();
+        let stash = Self::stash_for(dir)?;
+        let mut files: Vec<Self> = files_in(dir)?
+            .into_iter()
+            .map(|path| Self::new(path, &stash))
+            .collect();
+        // the stashed copy with the highest number, for each name
+        let mut stashed: BTreeMap<PathBuf, (usize, PathBuf)> = BTreeMap::new();
+        if stash.is_dir() {
+            for copy in files_in(&stash)? {
+                let name = copy.file_name().unwrap_or_default();
+                // only numbered if something was in the way, see `File`
+                let numbered = |&(rest, _): &(&str, usize)| {
+                    !exists(&dir.join(name)) && (exists(&stash.join(rest)) || exists(&dir.join(rest)))
+                };
+                let (path, n) = match name.to_str().and_then(split_number).filter(numbered) {
+                    Some((rest, n)) => (dir.join(rest), n),
+                    None => (dir.join(name), 0),
+                };
+                if !exists(&path) && stashed.get(&path).is_none_or(|(m, _)| n > *m) {
+                    stashed.insert(path, (n, copy));
+                }
+            }
+        }
+        files.extend(stashed.into_iter().map(|(path, (_, copy))| Self::with(path, copy, State::Disabled)));
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// The stash directory used for files in `dir`, which has to exist.
+    pub fn stash_for(dir: &Path) -> io::Result<PathBuf> {
+        sibling(dir, ".halfwit-stash")
+    }
+
+    /// Where the file is when it's disabled.
+    pub fn stashed_path(&self) -> &Path {
+        &self.stashed
+    }
+}
+
+impl Toggle for Stashed {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Stateful for Stashed {
+    fn set_state(&self, state: &State) -> Result<(), Error> {
+        self.state.set(*state, || match state {
+            State::Enabled => rename(&self.stashed, &self.path),
+            State::Disabled => {
+                if let Some(stash) = self.stashed.parent() {
+                    fs::create_dir_all(stash)?;
+                }
+                rename(&self.path, &self.stashed)
+            }
+        })
+    }
+
+    fn state(&self) -> State {
+        self.state.get()
+    }
+
+    fn verify_state(&self) -> Option<State> {
+        which_exists(&self.path, &self.stashed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::temp_dir;
+
+    #[test]
+    fn toggle() {
+        let dir = temp_dir("stash").join("mods");
+        fs::create_dir_all(&dir).unwrap();
+        let stash = Stashed::stash_for(&dir).unwrap();
+        assert_eq!(stash, fs::canonicalize(dir.parent().unwrap()).unwrap().join("mods.halfwit-stash"));
+        // however the directory is written, the stash goes next to it
+        assert_eq!(Stashed::stash_for(Path::new(&format!("{}/", dir.display()))).unwrap(), stash);
+        assert_eq!(Stashed::stash_for(&dir.join(".")).unwrap(), stash);
+        fs::create_dir_all(&stash).unwrap();
+        fs::write(dir.join("a.jar"), "").unwrap();
+        fs::write(stash.join("b.jar"), "").unwrap();
+        let files = Stashed::discover(&dir).unwrap();
+        assert_eq!(files.iter().map(Stashed::name).collect::<Vec<_>>(), ["a.jar", "b.jar"]);
+        assert_eq!(files[1].state(), State::Disabled);
+        files[0].set_state(&State::Disabled).unwrap();
+        files[1].set_state(&State::Enabled).unwrap();
+        assert!(stash.join("a.jar").exists());
+        assert!(!dir.join("a.jar").exists());
+        assert!(dir.join("b.jar").exists());
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    /// A leftover file in the stash shouldn't stop the real one from being
+    /// stashed, or get mixed up with it later.
+    #[test]
+    fn leftovers() {
+        let dir = temp_dir("stash-leftovers").join("mods");
+        fs::create_dir_all(&dir).unwrap();
+        let stash = Stashed::stash_for(&dir).unwrap();
+        fs::create_dir_all(&stash).unwrap();
+        fs::write(dir.join("a.jar"), "real").unwrap();
+        fs::write(stash.join("a.jar"), "leftover").unwrap();
+        fs::write(stash.join("libfoo.so.3"), "").unwrap();
+        let files = Stashed::discover(&dir).unwrap();
+        assert_eq!(files.iter().map(Stashed::name).collect::<Vec<_>>(), ["a.jar", "libfoo.so.3"]);
+        files[0].set_state(&State::Disabled).unwrap();
+        assert_eq!(fs::read_to_string(stash.join("a.jar.1")).unwrap(), "real");
+
+        // as if the run was interrupted
+        let files = Stashed::discover(&dir).unwrap();
+        assert_eq!(files.iter().map(Stashed::name).collect::<Vec<_>>(), ["a.jar", "libfoo.so.3"]);
+        files[0].set_state(&State::Enabled).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("a.jar")).unwrap(), "real");
+        assert_eq!(fs::read_to_string(stash.join("a.jar")).unwrap(), "leftover");
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+}
@@ -1,3 +1,8 @@
 #![allow(unused,dead_code)]
 
 pub mod bisection;
+pub mod confidence;
+pub mod error;
+pub mod file;
+pub mod strategy;
+pub mod tester;
@@ -1,4 +1,255 @@
 #![allow(unused,dead_code)]
-fn main() {
-    println!("Hello, world!");
+use std::{ffi::OsString, io, path::PathBuf, process::ExitCode, time::Duration};
+
+use halfwit::{
+    bisection::{Bisection, OnDrift, State, Stateful},
+    error::Error,
+    file::{File, Linked, Stashed, Toggle},
+    tester::{Cached, Command},
+};
+
+const USAGE: &str = "\
+usage: halfwit run --dir <DIR> [--toggle <HOW>] [--farm <DIR>] [--retries <N>]
+                   [--on-drift <WHAT>] -- <PROGRAM> [ARGS]...
+
+--toggle <HOW>  how files are disabled:
+                  rename       rename to <name>.disabled (the default)
+                  stash        move into <DIR>.halfwit-stash
+                  symlink      link only the enabled files into the farm
+                  permissions  take away read permissions (unix only)
+--farm <DIR>    where the symlinks go, defaults to <DIR>.halfwit-farm
+--retries <N>   how many times to try again, a second apart, when a file
+                can't be toggled, defaults to 0
+--on-drift <WHAT>
+                what to do when a file is toggled by something else mid-search:
+                  warn    keep going, and say so at the end (the default)
+                  repair  toggle it back
+                  abort   stop
+
+The program's exit code says how the enabled files behave: 0 means they're
+fine, and anything else (or a crash) means one of them is broken, except for
+125, which means it couldn't tell, like with `git bisect run`.
+
+The program gets HALFWIT_DIR, the directory it should load files from.";
+
+/// How files get disabled, see [halfwit::file].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum How {
+    Rename,
+    Stash,
+    Symlink,
+    Permissions,
+}
+
+/// Everything `halfwit run` was told to do.
+struct Run {
+    dir: PathBuf,
+    how: How,
+    farm: Option<PathBuf>,
+    retries: usize,
+    on_drift: OnDrift,
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl Run {
+    fn parse(mut args: impl Iterator<Item = OsString>) -> Result<Self, String> {
+        match args.next() {
+            Some(command) if command == "run" => {}
+            Some(command) => return Err(format!("unknown command {}", command.to_string_lossy())),
+            None => return Err("no command given".to_string()),
+        }
+        let mut dir = None;
+        let mut how = How::Rename;
+        let mut farm = None;
+        let mut retries = 0;
+        let mut on_drift = OnDrift::Warn;
+        let mut program = None;
+        while let Some(arg) = args.next() {
+            match arg.to_str() {
+                Some("--dir") => dir = Some(args.next().ok_or("--dir needs a value")?.into()),
+                Some("--farm") => farm = Some(args.next().ok_or("--farm needs a value")?.into()),
+                Some("--retries") => {
+                    retries = args
+                        .next()
+                        .ok_or("--retries needs a value")?
+                        .to_str()
+                        .and_then(|n| n.parse().ok())
+                        .ok_or("--retries must be a number")?
+                }
+                Some("--on-drift") => {
+                    on_drift = match args.next().ok_or("--on-drift needs a value")?.to_str() {
+                        Some("warn") => OnDrift::Warn,
+                        Some("repair") => OnDrift::Repair,
+                        Some("abort") => OnDrift::Abort,
+                        _ => return Err("--on-drift must be warn, repair or abort".to_string()),
+                    }
+                }
+                Some("--toggle") => {
+                    how = match args.next().ok_or("--toggle needs a value")?.to_str() {
+                        Some("rename") => How::Rename,
+                        Some("stash") => How::Stash,
+                        Some("symlink") => How::Symlink,
+                        Some("permissions") => How::Permissions,
+                        _ => return Err("--toggle must be rename, stash, symlink or permissions".to_string()),
+                    }
+                }
+                Some("--") => {
+                    program = args.next();
+                    break;
+                }
+                Some(flag) if flag.starts_with('-') => return Err(format!("unknown flag {flag}")),
+                _ => {
+                    program = Some(arg);
+                    break;
+                }
+            }
+        }
+        if farm.is_some() && how != How::Symlink {
+            return Err("--farm only makes sense with --toggle symlink".to_string());
+        }
+        Ok(Self {
+            dir: dir.ok_or("no --dir given")?,
+            how,
+            farm,
+            retries,
+            on_drift,
+            program: program.ok_or("no program given")?,
+            args: args.collect(),
+        })
+    }
+
+    /// The directory the program should load files from.
+    fn load_dir(&self) -> io::Result<PathBuf> {
+        match (self.how, &self.farm) {
+            (How::Symlink, Some(farm)) => Ok(farm.clone()),
+            (How::Symlink, None) => Linked::farm_for(&self.dir),
+            _ => Ok(self.dir.clone()),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let run = match Run::parse(std::env::args_os().skip(1)) {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("halfwit: {e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let load_dir = match run.load_dir() {
+        Ok(load_dir) => load_dir,
+        Err(e) => {
+            eprintln!("halfwit: can't read {}: {e}", run.dir.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut command = Command::new(run.program.clone(), run.args.clone());
+    command.env("HALFWIT_DIR", &load_dir);
+    let result = match run.how {
+        How::Rename => File::discover(&run.dir).map(|files| bisect(files, command, &run)),
+        How::Stash => Stashed::discover(&run.dir).map(|files| bisect(files, command, &run)),
+        How::Symlink => Linked::discover(&run.dir, &load_dir).map(|files| bisect(files, command, &run)),
+        #[cfg(unix)]
+        How::Permissions => {
+            halfwit::file::Unreadable::discover(&run.dir).map(|files| bisect(files, command, &run))
+        }
+        #[cfg(not(unix))]
+        How::Permissions => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "permissions can only be toggled on unix",
+        )),
+    };
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("halfwit: can't read {}: {e}", run.dir.display());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Search `files` for culprits, and print them.
+fn bisect<T: Toggle>(files: Vec<T>, mut command: Command, run: &Run) -> ExitCode {
+    let names: Vec<String> = files.iter().map(T::name).collect();
+    command.set_names(names.clone());
+
+    // the same configuration can come up more than once, no need to run it twice
+    let mut cached = Cached::new(command);
+    let states: Vec<State> = files.iter().map(T::state).collect();
+    let mut bisection = Bisection::new(files);
+    bisection.set_retries(run.retries, Duration::from_secs(1));
+    bisection.set_on_drift(run.on_drift);
+    // verified separately, so that it can't just be answered from the cache
+    bisection.set_verify(false);
+    let result = bisection.run(&mut cached).and_then(|_| {
+        cached.set_bypass(true);
+        bisection.verify(&mut cached)?;
+        Ok(bisection.report())
+    });
+    let command = cached.into_inner();
+
+    // put everything back the way it was, whether that worked or not
+    let mut failed = false;
+    for (file, state) in bisection.objects().iter().zip(&states) {
+        if let Err(e) = file.set_state(state) {
+            eprintln!("halfwit: couldn't restore {}: {e}", file.name());
+            failed = true;
+        }
+    }
+    eprintln!("halfwit: ran {} tests", command.runs());
+    let report = match result {
+        Ok(report) => report,
+        Err(Error::State { index, source, .. }) => {
+            eprintln!("halfwit: stopped, couldn't toggle {}: {source}", names[index]);
+            return ExitCode::FAILURE;
+        }
+        Err(Error::Drift { index, .. }) => {
+            eprintln!("halfwit: stopped, {} was toggled by something else", names[index]);
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("halfwit: stopped, {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for drift in &report.drift {
+        let what = match drift.repaired {
+            true => "toggled it back",
+            false => "results after that might be wrong",
+        };
+        eprintln!(
+            "halfwit: {} was {:?} instead of {:?} after {} results, {what}",
+            names[drift.index], drift.found, drift.expected, drift.after
+        );
+    }
+    for &i in &report.culprits {
+        println!("{}", names[i]);
+    }
+    for (i, culprits) in &report.implicated {
+        let culprits: Vec<&str> = culprits.iter().map(|&c| names[c].as_str()).collect();
+        eprintln!("halfwit: {} needs {}, so it couldn't be tested without it", names[*i], culprits.join(", "));
+    }
+    for interaction in &report.interactions {
+        let names: Vec<&str> = interaction.iter().map(|&i| names[i].as_str()).collect();
+        println!("{} (together)", names.join(" + "));
+    }
+    for &i in &report.indeterminate {
+        eprintln!("halfwit: couldn't tell about {}", names[i]);
+    }
+    if !report.contradictions.is_empty() {
+        eprintln!(
+            "halfwit: {} results contradicted each other, the test might be flaky",
+            report.contradictions.len()
+        );
+    }
+    if report.verification.as_ref().is_some_and(|v| !v.is_consistent()) {
+        eprintln!("halfwit: double-checking the answer didn't work out, it can't be trusted");
+        failed = true;
+    }
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
 }
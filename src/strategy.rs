@@ -0,0 +1,29 @@
+//! Ways of deciding which objects to test next.
+//!
+//! [Bisection](crate::bisection::Bisection) has its own built-in strategy,
+//! which splits dominant [Group](crate::bisection::Group)s in half. A
+//! [Strategy] can be used in its place with
+//! [Bisection::search](crate::bisection::Bisection::search), when there's
+//! some extra knowledge about the objects to take advantage of.
+
+pub mod bayesian;
+mod ddmin;
+mod splitting;
+
+pub use bayesian::Bayesian;
+pub use ddmin::Ddmin;
+pub use splitting::GeneralizedSplitting;
+
+use crate::bisection::{Behavior, Report};
+
+/// A step-by-step search for dominant objects.
+pub trait Strategy {
+    /// The indices of the objects that should be tested together next, in
+    /// order, or `None` if the search is over.
+    fn next_test(&self) -> Option<Vec<usize>>;
+    /// Record the behavior of a set of objects that was tested, as returned
+    /// by [Strategy::next_test].
+    fn record(&mut self, tested: &[usize], behavior: Behavior);
+    /// What the search has found so far.
+    fn report(&self) -> Report;
+}
@@ -0,0 +1,233 @@
+//! Probabilistic bisection, using what's already known about each object.
+
+use std::{
+    io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use super::Strategy;
+use crate::bisection::{Behavior, Report};
+
+/// Keeps track of how likely each object is to be dominant, and tests
+/// whichever set of objects tells us the most.
+///
+/// Each object starts with a prior probability of being dominant, which is
+/// updated after every test. A recessive test clears every object in it, and
+/// a dominant test makes every object in it more suspicious, in proportion to
+/// how suspicious it already was. The next test is picked so that it's as
+/// close to a coin flip as possible, which is where a test gives the most
+/// information.
+///
+/// An object is considered recessive once its probability falls to
+/// `threshold` or below, and dominant once it reaches `1 - threshold`.
+///
+/// Indeterminate tests don't change any probabilities, but the same set isn't
+/// tested again, and single objects that were indeterminate are given up on.
+///
+/// # Examples
+/// ```
+/// # use halfwit::bisection::Behavior;
+/// # use halfwit::strategy::{Bayesian, Strategy};
+/// // the last mod was added yesterday, so it's the prime suspect
+/// let mut bayesian = Bayesian::new(vec![0.01, 0.01, 0.01, 0.01, 0.01, 0.01, 0.9]);
+/// assert_eq!(bayesian.next_test(), Some(vec![6]));
+/// bayesian.record(&[6], Behavior::Dominant);
+/// assert_eq!(bayesian.probabilities()[6], 1.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bayesian {
+    priors: Vec<f64>,
+    probabilities: Vec<f64>,
+    observations: Vec<(Vec<usize>, Behavior)>,
+    threshold: f64,
+}
+
+impl Bayesian {
+    /// Start from a prior probability of being dominant for each object.
+    pub fn new(priors: Vec<f64>) -> Self {
+        Self {
+            probabilities: priors.clone(),
+            priors,
+            observations: Vec::new(),
+            threshold: 0.001,
+        }
+    }
+
+    /// Start with `n` objects that are all equally suspicious.
+    pub fn uniform(n: usize, prior: f64) -> Self {
+        Self::new(vec![prior; n])
+    }
+
+    /// Start with priors based on how recently each file was modified, see
+    /// [recency_prior].
+    pub fn from_files<P: AsRef<Path>>(paths: &[P], half_life: Duration) -> io::Result<Self> {
+        let now = SystemTime::now();
+        let priors = paths
+            .iter()
+            .map(|p| Ok(recency_prior(p.as_ref().metadata()?.modified()?, now, half_life)))
+            .collect::<io::Result<_>>()?;
+        Ok(Self::new(priors))
+    }
+
+    /// Set how close to 0 or 1 a probability needs to be for an object to be
+    /// considered decided. Defaults to 0.001.
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.threshold = threshold;
+    }
+
+    /// The current probability of each object being dominant.
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    fn is_decided(&self, i: usize) -> bool {
+        let p = self.probabilities[i];
+        p <= self.threshold || p >= 1.0 - self.threshold
+    }
+
+    fn is_skipped(&self, tested: &[usize]) -> bool {
+        self.observations
+            .iter()
+            .any(|(t, b)| *b == Behavior::Indeterminate && t == tested)
+    }
+
+    /// Work out every probability again from the priors and observations.
+    fn update(&mut self) {
+        let mut p = self.priors.clone();
+        for (tested, _) in self.observations.iter().filter(|(_, b)| *b == Behavior::Recessive) {
+            for &i in tested {
+                p[i] = 0.0;
+            }
+        }
+        for (tested, _) in self.observations.iter().filter(|(_, b)| *b == Behavior::Dominant) {
+            // chance that at least one of them is dominant
+            let any = 1.0 - tested.iter().map(|&i| 1.0 - p[i]).product::<f64>();
+            // if everything was cleared, the results contradict each other,
+            // so there's nothing sensible to update
+            if any > 0.0 {
+                for &i in tested {
+                    p[i] = (p[i] / any).min(1.0);
+                }
+            }
+        }
+        self.probabilities = p;
+    }
+}
+
+impl Strategy for Bayesian {
+    fn next_test(&self) -> Option<Vec<usize>> {
+        let mut candidates: Vec<usize> = (0..self.probabilities.len())
+            .filter(|&i| !self.is_decided(i) && !self.is_skipped(&[i]))
+            .collect();
+        // a test is recessive with probability e^-weight, so aim for a total
+        // weight of ln 2, an even chance either way
+        let weight = |i: usize| -(1.0 - self.probabilities[i]).ln();
+        candidates.sort_by(|&a, &b| weight(b).total_cmp(&weight(a)));
+        let target = std::f64::consts::LN_2;
+        let mut total = 0.0;
+        let mut test = Vec::new();
+        for i in candidates {
+            if test.is_empty() || (total + weight(i) - target).abs() < (total - target).abs() {
+                total += weight(i);
+                test.push(i);
+            }
+        }
+        test.sort_unstable();
+        while self.is_skipped(&test) {
+            test.truncate(test.len().div_ceil(2));
+        }
+        match test.is_empty() {
+            true => None,
+            false => Some(test),
+        }
+    }
+
+    fn record(&mut self, tested: &[usize], behavior: Behavior) {
+        self.observations.push((tested.to_vec(), behavior));
+        self.update();
+    }
+
+    fn report(&self) -> Report {
+        let culprits: Vec<usize> = (0..self.probabilities.len())
+            .filter(|&i| self.probabilities[i] >= 1.0 - self.threshold)
+            .collect();
+        let indeterminate = (0..self.probabilities.len())
+            .filter(|&i| !self.is_decided(i) && self.is_skipped(&[i]))
+            .collect();
+        Report {
+            confidence: culprits.iter().map(|&i| (i, self.probabilities[i])).collect(),
+            culprits,
+            indeterminate,
+            ..Default::default()
+        }
+    }
+}
+
+/// A prior for a file last modified at `modified`.
+///
+/// Files modified just now start at 0.5, halving every `half_life`, down to a
+/// floor of 0.01.
+///
+/// # Examples
+/// ```
+/// # use std::time::{Duration, SystemTime};
+/// # use halfwit::strategy::bayesian::recency_prior;
+/// let day = Duration::from_secs(60 * 60 * 24);
+/// let now = SystemTime::now();
+/// assert_eq!(recency_prior(now, now, day), 0.5);
+/// assert_eq!(recency_prior(now - day, now, day), 0.25);
+/// assert_eq!(recency_prior(now - day * 365, now, day), 0.01);
+/// ```
+pub fn recency_prior(modified: SystemTime, now: SystemTime, half_life: Duration) -> f64 {
+    let age = now.duration_since(modified).unwrap_or_default();
+    let halvings = age.as_secs_f64() / half_life.as_secs_f64();
+    (0.5 * 0.5f64.powf(halvings)).max(0.01)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bisection::tests::{any_of, setup};
+
+    #[test]
+    fn finds_culprits() {
+        for culprits in [vec![], vec![0], vec![9], vec![2, 7], vec![3, 4, 5]] {
+            let mut bisection = setup(10);
+            let report = bisection.search(&mut Bayesian::uniform(10, 0.1), any_of(&culprits)).unwrap();
+            assert_eq!(report.culprits, culprits);
+        }
+    }
+
+    #[test]
+    fn indeterminate() {
+        let mut bisection = setup(10);
+        let mut test = any_of(&[6]);
+        let report = bisection.search(&mut Bayesian::uniform(10, 0.1), |enabled: &[usize]| {
+            match enabled.contains(&2) {
+                true => Behavior::Indeterminate,
+                false => test(enabled),
+            }
+        }).unwrap();
+        assert_eq!(report.culprits, vec![6]);
+        assert_eq!(report.indeterminate, vec![2]);
+    }
+
+    /// A good prior should take fewer tests than a flat one.
+    #[test]
+    fn priors_help() {
+        let count = |priors: Vec<f64>| {
+            let mut bisection = setup(32);
+            let mut runs = 0;
+            let mut test = any_of(&[20]);
+            bisection.search(&mut Bayesian::new(priors), |enabled: &[usize]| {
+                runs += 1;
+                test(enabled)
+            }).unwrap();
+            runs
+        };
+        let mut informed = vec![0.02; 32];
+        informed[20] = 0.8;
+        assert!(count(informed) < count(vec![0.05; 32]));
+    }
+}
@@ -0,0 +1,190 @@
+//! Zeller's delta debugging, for failures that need several objects at once.
+
+use super::Strategy;
+use crate::bisection::{Behavior, Report};
+
+/// Which of the current set's pieces is being tested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Testing the `i`th piece on its own.
+    Subset(usize),
+    /// Testing everything but the `i`th piece.
+    Complement(usize),
+    /// The current set is 1-minimal.
+    Done,
+}
+
+/// Shrinks a dominant set of objects down to a 1-minimal one, using `ddmin`.
+///
+/// The set is cut into `n` pieces. If any piece is dominant on its own, it
+/// becomes the new set. Otherwise, if leaving any one piece out is still
+/// dominant, what's left becomes the new set. Otherwise the pieces are made
+/// smaller, until they're single objects. The end result is still dominant,
+/// but removing any one object from it makes it recessive, which makes it the
+/// right tool for failures that only happen when several objects are enabled
+/// together.
+///
+/// Indeterminate results are treated like recessive ones.
+///
+/// # Examples
+/// ```
+/// # use halfwit::bisection::Behavior::*;
+/// # use halfwit::strategy::{Ddmin, Strategy};
+/// // 1 and 6 only crash together
+/// let mut ddmin = Ddmin::new((0..8).collect());
+/// while let Some(test) = ddmin.next_test() {
+///     match test.contains(&1) && test.contains(&6) {
+///         true => ddmin.record(&test, Dominant),
+///         false => ddmin.record(&test, Recessive),
+///     }
+/// }
+/// assert_eq!(ddmin.minimal(), Some(&[1, 6][..]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ddmin {
+    /// The smallest set known to be dominant so far.
+    current: Vec<usize>,
+    /// How many pieces `current` is cut into.
+    pieces: usize,
+    phase: Phase,
+}
+
+impl Ddmin {
+    /// Start shrinking `dominant`, which must already be known to be dominant.
+    pub fn new(dominant: Vec<usize>) -> Self {
+        Self::with_pieces(dominant, 2)
+    }
+
+    /// Like [Ddmin::new], but cut the set into `pieces` to begin with.
+    ///
+    /// This saves a few tests when it's already known that smaller pieces are
+    /// recessive, such as when both halves of the set were already tested.
+    pub fn with_pieces(dominant: Vec<usize>, pieces: usize) -> Self {
+        let mut ddmin = Self {
+            pieces: pieces.clamp(2, dominant.len().max(2)),
+            current: dominant,
+            phase: Phase::Subset(0),
+        };
+        if ddmin.current.len() <= 1 {
+            ddmin.phase = Phase::Done;
+        }
+        ddmin
+    }
+
+    /// The 1-minimal dominant set, once the search is over.
+    pub fn minimal(&self) -> Option<&[usize]> {
+        match self.phase {
+            Phase::Done => Some(&self.current),
+            _ => None,
+        }
+    }
+
+    /// The smallest dominant set found so far.
+    pub fn current(&self) -> &[usize] {
+        &self.current
+    }
+
+    /// The `i`th of `current`'s pieces.
+    fn piece(&self, i: usize) -> &[usize] {
+        let len = self.current.len();
+        &self.current[i * len / self.pieces..(i + 1) * len / self.pieces]
+    }
+
+    fn complement(&self, i: usize) -> Vec<usize> {
+        let piece = self.piece(i);
+        self.current.iter().copied().filter(|x| !piece.contains(x)).collect()
+    }
+
+    /// Make `dominant` the new current set, cut into `pieces`.
+    fn shrink(&mut self, dominant: Vec<usize>, pieces: usize) {
+        *self = Self::with_pieces(dominant, pieces);
+    }
+
+    /// Move on after a piece or complement wasn't dominant.
+    fn advance(&mut self) {
+        self.phase = match self.phase {
+            Phase::Subset(i) if i + 1 < self.pieces => Phase::Subset(i + 1),
+            // with 2 pieces, the complements are just the other piece
+            Phase::Subset(_) if self.pieces > 2 => Phase::Complement(0),
+            Phase::Complement(i) if i + 1 < self.pieces => Phase::Complement(i + 1),
+            _ if self.pieces < self.current.len() => {
+                self.pieces = (self.pieces * 2).min(self.current.len());
+                Phase::Subset(0)
+            },
+            _ => Phase::Done,
+        };
+    }
+}
+
+impl Strategy for Ddmin {
+    fn next_test(&self) -> Option<Vec<usize>> {
+        match self.phase {
+            Phase::Subset(i) => Some(self.piece(i).to_vec()),
+            Phase::Complement(i) => Some(self.complement(i)),
+            Phase::Done => None,
+        }
+    }
+
+    fn record(&mut self, tested: &[usize], behavior: Behavior) {
+        match (self.phase, behavior) {
+            (Phase::Subset(_), Behavior::Dominant) => self.shrink(tested.to_vec(), 2),
+            (Phase::Complement(_), Behavior::Dominant) => {
+                let pieces = self.pieces - 1;
+                self.shrink(tested.to_vec(), pieces)
+            },
+            (Phase::Done, _) => {},
+            _ => self.advance(),
+        }
+    }
+
+    fn report(&self) -> Report {
+        match self.minimal() {
+            Some(&[culprit]) => Report {
+                culprits: vec![culprit],
+                ..Default::default()
+            },
+            Some(minimal) => Report {
+                interactions: vec![minimal.to_vec()],
+                ..Default::default()
+            },
+            None => Report::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minimize(set: Vec<usize>, fails: impl Fn(&[usize]) -> bool) -> (Vec<usize>, usize) {
+        let mut ddmin = Ddmin::new(set);
+        let mut runs = 0;
+        while let Some(test) = ddmin.next_test() {
+            runs += 1;
+            match fails(&test) {
+                true => ddmin.record(&test, Behavior::Dominant),
+                false => ddmin.record(&test, Behavior::Recessive),
+            }
+        }
+        (ddmin.minimal().unwrap().to_vec(), runs)
+    }
+
+    #[test]
+    fn minimal() {
+        let all = |needed: &'static [usize]| move |test: &[usize]| needed.iter().all(|i| test.contains(i));
+        assert_eq!(minimize((0..8).collect(), all(&[3])).0, vec![3]);
+        assert_eq!(minimize((0..8).collect(), all(&[0, 7])).0, vec![0, 7]);
+        assert_eq!(minimize((0..16).collect(), all(&[2, 3, 9])).0, vec![2, 3, 9]);
+        assert_eq!(minimize(vec![5], all(&[5])), (vec![5], 0));
+    }
+
+    /// Either of two pairs is enough, so the result is one of them.
+    #[test]
+    fn one_minimal() {
+        let fails = |test: &[usize]| {
+            (test.contains(&1) && test.contains(&2)) || (test.contains(&5) && test.contains(&6))
+        };
+        let (minimal, _) = minimize((0..8).collect(), fails);
+        assert!(minimal == vec![1, 2] || minimal == vec![5, 6]);
+    }
+}
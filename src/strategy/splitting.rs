@@ -0,0 +1,203 @@
+//! Hwang's generalized binary splitting, for when the number of culprits is
+//! roughly known.
+
+use super::Strategy;
+use crate::bisection::{Behavior, Report};
+
+/// Adaptive group testing with a known number of culprits.
+///
+/// With `n` undecided objects and `d` culprits expected among them, a group
+/// of `2^α` objects is tested, where `α = ⌊log2((n - d + 1) / d)⌋`. A
+/// recessive group is cleared all at once, and a dominant one is halved until
+/// a culprit is found, clearing every recessive half along the way. Once
+/// there are so few objects left that groups stop paying off (`n <= 2d - 2`)
+/// they're tested one at a time. This takes about `d log2(n / d)` tests,
+/// which is far fewer than splitting everything in half when `d` is small
+/// compared to `n`.
+///
+/// If more culprits turn up than expected, the rest of the objects are tested
+/// all together, and halved as usual if that's dominant.
+///
+/// Unlike [Bisection](crate::bisection::Bisection), results have to be
+/// recorded in the order [Strategy::next_test] gives them.
+///
+/// # Examples
+/// ```
+/// # use halfwit::bisection::Behavior::*;
+/// # use halfwit::strategy::{GeneralizedSplitting, Strategy};
+/// // 1 culprit expected among 16, so test the first 2^4 = 16
+/// let mut splitting = GeneralizedSplitting::new(16, 1);
+/// assert_eq!(splitting.next_test(), Some((0..16).collect()));
+/// // 2 culprits expected among 16, so test the first 2^2 = 4
+/// let mut splitting = GeneralizedSplitting::new(16, 2);
+/// assert_eq!(splitting.next_test(), Some(vec![0, 1, 2, 3]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeneralizedSplitting {
+    /// Objects that haven't been decided yet, in order.
+    remaining: Vec<usize>,
+    /// How many culprits are still expected among `remaining`.
+    expected: usize,
+    /// A set known to contain a culprit, which is being halved.
+    suspects: Option<Vec<usize>>,
+    /// Sets that have to be tested on their own, because testing them as
+    /// part of a bigger set was indeterminate.
+    queue: Vec<Vec<usize>>,
+    culprits: Vec<usize>,
+    indeterminate: Vec<usize>,
+}
+
+impl GeneralizedSplitting {
+    /// Search `n` objects, expecting about `expected` of them to be culprits.
+    pub fn new(n: usize, expected: usize) -> Self {
+        Self {
+            remaining: (0..n).collect(),
+            expected,
+            suspects: None,
+            queue: Vec::new(),
+            culprits: Vec::new(),
+            indeterminate: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self, tested: &[usize]) {
+        self.remaining.retain(|i| !tested.contains(i));
+    }
+
+    /// A set known to contain a culprit, which gets halved until the culprit
+    /// is found.
+    fn suspect(&mut self, suspects: Vec<usize>) {
+        if let [culprit] = suspects[..] {
+            self.clear(&[culprit]);
+            self.culprits.push(culprit);
+            self.expected = self.expected.saturating_sub(1);
+            self.suspects = None;
+        } else {
+            self.suspects = Some(suspects);
+        }
+    }
+
+    /// Give up on a set, unless it can be split up and tested in halves.
+    fn skip(&mut self, tested: &[usize]) {
+        match tested.len() {
+            1 => {
+                self.clear(tested);
+                self.indeterminate.push(tested[0]);
+            },
+            len => {
+                self.queue.push(tested[len / 2..].to_vec());
+                self.queue.push(tested[..len / 2].to_vec());
+            },
+        }
+    }
+}
+
+impl Strategy for GeneralizedSplitting {
+    fn next_test(&self) -> Option<Vec<usize>> {
+        if let Some(suspects) = &self.suspects {
+            return Some(suspects[..suspects.len() / 2].to_vec());
+        }
+        if let Some(next) = self.queue.last() {
+            return Some(next.clone());
+        }
+        let (n, d) = (self.remaining.len(), self.expected);
+        let size = match () {
+            _ if n == 0 => return None,
+            _ if d == 0 => n,
+            _ if n <= 2 * d - 2 => 1,
+            // 2^⌊log2(l / d)⌋, where l = n - d + 1
+            _ => 1 << ((n - d + 1) / d).ilog2(),
+        };
+        Some(self.remaining[..size].to_vec())
+    }
+
+    fn record(&mut self, tested: &[usize], behavior: Behavior) {
+        if let Some(suspects) = self.suspects.take() {
+            let (half, rest) = suspects.split_at(tested.len());
+            match behavior {
+                Behavior::Recessive => {
+                    self.clear(half);
+                    self.suspect(rest.to_vec());
+                },
+                Behavior::Dominant => self.suspect(half.to_vec()),
+                // `rest` isn't known to have a culprit any more, so it goes
+                // back with the rest
+                _ => self.skip(half),
+            }
+            return;
+        }
+        if self.queue.last().map(Vec::as_slice) == Some(tested) {
+            self.queue.pop();
+        }
+        match behavior {
+            Behavior::Recessive => self.clear(tested),
+            Behavior::Dominant => self.suspect(tested.to_vec()),
+            _ => self.skip(tested),
+        }
+    }
+
+    fn report(&self) -> Report {
+        let mut culprits = self.culprits.clone();
+        culprits.sort_unstable();
+        let mut indeterminate = self.indeterminate.clone();
+        indeterminate.sort_unstable();
+        Report {
+            culprits,
+            indeterminate,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bisection::tests::{any_of, setup};
+
+    #[test]
+    fn finds_culprits() {
+        for expected in [0, 1, 2, 5] {
+            for culprits in [vec![], vec![0], vec![15], vec![2, 7], vec![3, 4, 5, 12]] {
+                let mut bisection = setup(16);
+                let mut splitting = GeneralizedSplitting::new(16, expected);
+                let report = bisection.search(&mut splitting, any_of(&culprits)).unwrap();
+                assert_eq!(report.culprits, culprits);
+            }
+        }
+    }
+
+    /// With few culprits among many objects, this should beat halving.
+    #[test]
+    fn fewer_tests() {
+        let culprits = [9, 40];
+        let mut counted = |use_splitting: bool| {
+            let mut bisection = setup(64);
+            let mut runs = 0;
+            let mut test = any_of(&culprits);
+            let tester = |enabled: &[usize]| {
+                runs += 1;
+                test(enabled)
+            };
+            match use_splitting {
+                true => bisection.search(&mut GeneralizedSplitting::new(64, 2), tester).unwrap(),
+                false => bisection.run(tester).unwrap(),
+            };
+            runs
+        };
+        assert!(counted(true) < counted(false));
+    }
+
+    #[test]
+    fn indeterminate() {
+        let mut bisection = setup(16);
+        let mut test = any_of(&[11]);
+        let report = bisection.search(&mut GeneralizedSplitting::new(16, 1), |enabled: &[usize]| {
+            match enabled.contains(&4) {
+                true => Behavior::Indeterminate,
+                false => test(enabled),
+            }
+        }).unwrap();
+        assert_eq!(report.culprits, vec![11]);
+        assert_eq!(report.indeterminate, vec![4]);
+    }
+}
@@ -0,0 +1,289 @@
+//! Running tests on a set of enabled objects.
+//!
+//! A [Tester] is whatever actually decides how a set of objects behaves, be
+//! that launching a game, running a script, or asking a human. Any closure
+//! taking the enabled indices and returning a [Behavior] is a [Tester].
+
+use std::{collections::HashMap, ffi::OsString, process};
+
+use crate::{bisection::Behavior, error::Error};
+
+/// Something that can test how the currently enabled objects behave.
+pub trait Tester {
+    /// Test the current configuration.
+    ///
+    /// `enabled` holds the indices of every enabled object, in order. By the
+    /// time this is called, every object has already been set to the right
+    /// [State](crate::bisection::State).
+    fn test(&mut self, enabled: &[usize]) -> Behavior;
+
+    /// A short description of how the last test failed, such as an exit code
+    /// or the last line of a crash log, if there's anything to say.
+    ///
+    /// This is what tells different failures apart, see
+    /// [Bisection::peel](crate::bisection::Bisection::peel).
+    fn signature(&self) -> Option<String> {
+        None
+    }
+
+    /// Something that went wrong badly enough that the search should stop,
+    /// like the program not existing, rather than just coming back
+    /// [Behavior::Indeterminate]. This is checked after every test.
+    fn take_error(&mut self) -> Option<Error> {
+        None
+    }
+}
+
+impl<F: FnMut(&[usize]) -> Behavior> Tester for F {
+    fn test(&mut self, enabled: &[usize]) -> Behavior {
+        self(enabled)
+    }
+}
+
+/// A [Tester] that remembers the result for every set of enabled objects, and
+/// reuses it instead of running the test again.
+///
+/// Different branches of a search, reverification, and resumed sessions can
+/// all end up testing the exact same configuration. Indeterminate results
+/// aren't remembered, since whatever went wrong might not happen again.
+///
+/// Flaky tests shouldn't be cached, since running them again is the whole
+/// point of a [Policy](crate::confidence::Policy) other than
+/// [Policy::Once](crate::confidence::Policy::Once). For those, the cache can
+/// be bypassed with [Cached::set_bypass].
+///
+/// # Examples
+/// ```
+/// # use halfwit::bisection::Behavior;
+/// # use halfwit::tester::{Cached, Tester};
+/// let mut runs = 0;
+/// let mut cached = Cached::new(|_: &[usize]| {
+///     runs += 1;
+///     Behavior::Recessive
+/// });
+/// cached.test(&[1, 2, 3]);
+/// cached.test(&[1, 2, 3]);
+/// cached.test(&[1, 2]);
+/// assert_eq!(cached.hits(), 1);
+/// drop(cached);
+/// assert_eq!(runs, 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cached<X: Tester> {
+    tester: X,
+    results: HashMap<Vec<usize>, (Behavior, Option<String>)>,
+    bypass: bool,
+    hits: usize,
+    /// The signature of the last result, cached or not.
+    signature: Option<String>,
+}
+
+impl<X: Tester> Cached<X> {
+    pub fn new(tester: X) -> Self {
+        Self {
+            tester,
+            results: HashMap::new(),
+            bypass: false,
+            hits: 0,
+            signature: None,
+        }
+    }
+
+    /// Set whether to always run the test, instead of looking at the cache.
+    /// Results are still remembered either way. Defaults to `false`.
+    pub fn set_bypass(&mut self, bypass: bool) {
+        self.bypass = bypass;
+    }
+
+    /// How many tests were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Every remembered result, to save for resuming a session later.
+    pub fn results(&self) -> impl Iterator<Item = (&[usize], Behavior)> {
+        self.results.iter().map(|(k, (v, _))| (k.as_slice(), *v))
+    }
+
+    /// Remember a result from elsewhere, such as a previous session.
+    pub fn insert(&mut self, enabled: Vec<usize>, behavior: Behavior) {
+        self.results.insert(enabled, (behavior, None));
+    }
+
+    pub fn into_inner(self) -> X {
+        self.tester
+    }
+}
+
+impl<X: Tester> Tester for Cached<X> {
+    fn test(&mut self, enabled: &[usize]) -> Behavior {
+        if !self.bypass {
+            if let Some((behavior, signature)) = self.results.get(enabled) {
+                self.hits += 1;
+                self.signature = signature.clone();
+                return *behavior;
+            }
+        }
+        let behavior = self.tester.test(enabled);
+        self.signature = self.tester.signature();
+        if behavior != Behavior::Indeterminate {
+            self.results.insert(enabled.to_vec(), (behavior, self.signature.clone()));
+        }
+        behavior
+    }
+
+    fn signature(&self) -> Option<String> {
+        self.signature.clone()
+    }
+
+    fn take_error(&mut self) -> Option<Error> {
+        self.tester.take_error()
+    }
+}
+
+// So the cache can be kept around after a search, to be reused
+impl<X: Tester> Tester for &mut Cached<X> {
+    fn test(&mut self, enabled: &[usize]) -> Behavior {
+        Cached::test(self, enabled)
+    }
+
+    fn signature(&self) -> Option<String> {
+        Cached::signature(self)
+    }
+
+    fn take_error(&mut self) -> Option<Error> {
+        Cached::take_error(self)
+    }
+}
+
+/// A [Tester] that runs a program, and judges it by its exit code.
+///
+/// Exit code 0 is recessive, and anything else is dominant, including being
+/// killed by a signal (a crash), except for 125, which is indeterminate, like
+/// with `git bisect run`. A program that can't be started at all is an error,
+/// see [Tester::take_error].
+///
+/// The program gets a few environment variables:
+/// - `HALFWIT_RUN`, how many tests were ran before this one.
+/// - `HALFWIT_ENABLED`, the names of the enabled objects, one per line, if
+///   names were given with [Command::set_names].
+///
+/// More can be added with [Command::env].
+#[derive(Debug, Clone)]
+pub struct Command {
+    program: OsString,
+    args: Vec<OsString>,
+    names: Vec<String>,
+    env: Vec<(OsString, OsString)>,
+    runs: usize,
+    signature: Option<String>,
+    /// Why the program couldn't be started.
+    error: Option<String>,
+}
+
+impl Command {
+    pub fn new(program: impl Into<OsString>, args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            names: Vec::new(),
+            env: Vec::new(),
+            runs: 0,
+            signature: None,
+            error: None,
+        }
+    }
+
+    /// Names for each object, by index, to pass on to the program.
+    pub fn set_names(&mut self, names: Vec<String>) {
+        self.names = names;
+    }
+
+    /// Set an environment variable for the program.
+    pub fn env(&mut self, key: impl Into<OsString>, value: impl Into<OsString>) {
+        self.env.push((key.into(), value.into()));
+    }
+
+    /// How many times the program was ran.
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+}
+
+impl Tester for Command {
+    fn test(&mut self, enabled: &[usize]) -> Behavior {
+        let names: Vec<&str> = enabled.iter().filter_map(|&i| self.names.get(i)).map(String::as_str).collect();
+        let status = process::Command::new(&self.program)
+            .args(&self.args)
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
+            .env("HALFWIT_RUN", self.runs.to_string())
+            .env("HALFWIT_ENABLED", names.join("\n"))
+            .status();
+        self.runs += 1;
+        let (behavior, signature) = match status {
+            Ok(status) => match status.code() {
+                Some(0) => (Behavior::Recessive, None),
+                Some(125) => (Behavior::Indeterminate, Some("exit code 125".to_string())),
+                Some(code) => (Behavior::Dominant, Some(format!("exit code {code}"))),
+                // killed by a signal
+                None => (Behavior::Dominant, Some(status.to_string())),
+            },
+            Err(e) => {
+                self.error = Some(format!("couldn't run {}: {e}", self.program.to_string_lossy()));
+                (Behavior::Indeterminate, Some(e.to_string()))
+            }
+        };
+        self.signature = signature;
+        behavior
+    }
+
+    fn signature(&self) -> Option<String> {
+        self.signature.clone()
+    }
+
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take().map(Error::Other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bisection::tests::{any_of, setup};
+    use std::cell::Cell;
+
+    /// Searching twice with the same cache shouldn't run anything the second
+    /// time around.
+    #[test]
+    fn cached_search() {
+        let runs = Cell::new(0);
+        let mut culprit = any_of(&[2, 5]);
+        let mut cached = Cached::new(|enabled: &[usize]| {
+            runs.set(runs.get() + 1);
+            culprit(enabled)
+        });
+        let first = setup(8).run(&mut cached).unwrap();
+        let first_runs = runs.get();
+        let second = setup(8).run(&mut cached).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(runs.get(), first_runs);
+        cached.set_bypass(true);
+        setup(8).run(&mut cached).unwrap();
+        assert!(runs.get() > first_runs);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command() {
+        let mut sh = |script: &str| Command::new("sh", ["-c", script]).test(&[]);
+        assert_eq!(sh("exit 0"), Behavior::Recessive);
+        assert_eq!(sh("exit 3"), Behavior::Dominant);
+        assert_eq!(sh("exit 125"), Behavior::Indeterminate);
+        assert_eq!(sh("kill -SEGV $$"), Behavior::Dominant);
+
+        let mut missing = Command::new("/nonexistent/halfwit-test", Vec::<String>::new());
+        assert_eq!(missing.test(&[]), Behavior::Indeterminate);
+        assert!(missing.take_error().is_some());
+        assert!(setup(8).run(&mut Cached::new(missing)).is_err());
+    }
+}